@@ -1,7 +1,46 @@
-use criterion::{Criterion, criterion_group, criterion_main};
+use advent_of_code_2022::days::{self, Solution};
+use advent_of_code_2022::utility::utils::read_file;
+use criterion::{black_box, Criterion, criterion_group, criterion_main};
+
+// Parse, part 1 and part 2 are timed separately; reading the input file happens once, up front.
+fn bench_day<S: Solution>(c: &mut Criterion, number: u8, sample_size: usize) {
+    let day = days::get(number).unwrap();
+    let contents = match read_file(&day.input_path()) {
+        Ok(contents) => contents,
+        Err(error) => {
+            eprintln!("Skipping day {:02}: {}", number, error);
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group(format!("day{:02}", number));
+    group.sample_size(sample_size);
+
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&contents))));
+
+    let input = S::parse(&contents).unwrap();
+    group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&input))));
+    group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&input))));
+
+    group.finish();
+}
 
 fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("Day 14", |b| b.iter(|| { days::day14::solve() }));
+    bench_day::<days::day01::Day01>(c, 1, 100);
+    bench_day::<days::day02::Day02>(c, 2, 100);
+    bench_day::<days::day03::Day03>(c, 3, 100);
+    bench_day::<days::day04::Day04>(c, 4, 100);
+    bench_day::<days::day05::Day05>(c, 5, 100);
+    bench_day::<days::day06::Day06>(c, 6, 100);
+    bench_day::<days::day07::Day07>(c, 7, 100);
+    bench_day::<days::day08::Day08>(c, 8, 100);
+    bench_day::<days::day09::Day09>(c, 9, 100);
+    bench_day::<days::day10::Day10>(c, 10, 100);
+    bench_day::<days::day11::Day11>(c, 11, 100);
+    bench_day::<days::day12::Day12>(c, 12, 10);
+    bench_day::<days::day13::Day13>(c, 13, 100);
+    bench_day::<days::day14::Day14>(c, 14, 100);
+    bench_day::<days::day15::Day15>(c, 15, 10);
 }
 
 criterion_group!(benches, criterion_benchmark);
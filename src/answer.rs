@@ -0,0 +1,139 @@
+use std::fmt::{Display, Formatter};
+
+/// What a part returns, kept typed so it can be written out as text, JSON or CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    Grid(Vec<String>), // One string per row, e.g. letters drawn on a screen
+    None // Nothing to find, e.g. when a parameter leaves no room for it
+}
+
+impl Answer {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "string",
+            Answer::Grid(_) => "grid",
+            Answer::None => "none"
+        }
+    }
+
+    /// Integers as numbers, text as a string, grids as an array of row strings and no answer as `null`.
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Integer(n) => n.to_string(),
+            Answer::Text(text) => json_string(text),
+            Answer::Grid(rows) => {
+                let rows: Vec<String> = rows.iter().map(|row| json_string(row)).collect();
+                format!("[{}]", rows.join(", "))
+            },
+            Answer::None => "null".to_string()
+        }
+    }
+}
+
+// Grids are their rows separated by line breaks, as written in answers files and CSV
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::None => write!(f, "none")
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Integer(i64::try_from(n).expect("answer does not fit in an i64"))
+            }
+        })*
+    };
+}
+
+integer_answer!(i32, u32, i64, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(answer: Option<T>) -> Self {
+        answer.map_or(Answer::None, Into::into)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Self {
+        Answer::Grid(rows)
+    }
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut result = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c)
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Quotes a CSV field only when it needs it.
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("plain ⚪"), r#""plain ⚪""#);
+        assert_eq!(json_string(r#"say "hi" \o/"#), r#""say \"hi\" \\o/""#);
+        assert_eq!(json_string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(json_string("\u{0}\u{1b}\u{7f}"), r#""\u0000\u001b\u007f""#);
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("12345"), "12345");
+        assert_eq!(csv_field("a,b"), r#""a,b""#);
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(csv_field("#..#\n.##."), "\"#..#\n.##.\"");
+        assert_eq!(csv_field("a\r\nb"), "\"a\r\nb\"");
+    }
+
+    #[test]
+    fn answers_as_json() {
+        assert_eq!(Answer::from(-3_i32).to_json(), "-3");
+        assert_eq!(Answer::from("CMZ".to_string()).to_json(), r#""CMZ""#);
+        assert_eq!(Answer::from(Some(7_i64)), Answer::Integer(7));
+        assert_eq!(Answer::from(None::<i64>), Answer::None);
+        assert_eq!((Answer::None.kind(), Answer::None.to_json()), ("none", "null".to_string()));
+
+        let grid = Answer::from(vec![r#"#"\"#.to_string(), "..".to_string()]);
+        assert_eq!(grid.kind(), "grid");
+        assert_eq!(grid.to_json(), r##"["#\"\\", ".."]"##);
+        assert_eq!(Answer::Grid(vec![]).to_json(), "[]");
+        assert_eq!(grid.to_string(), "#\"\\\n..");
+    }
+}
@@ -0,0 +1,630 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use advent_of_code_2022::answer::{csv_field, json_string, Answer};
+use advent_of_code_2022::days::{self, Day, Part};
+use advent_of_code_2022::settings::{self, ConfigEntry, Settings};
+use advent_of_code_2022::timing::{Pretty, Stats, Timing};
+use advent_of_code_2022::utility::utils::{catch_panic, read_file, without_panic_messages};
+use advent_of_code_2022::verify::{self, Check};
+
+pub const USAGE: &str = "Usage:
+    advent-of-code-2022 list
+    advent-of-code-2022 run <day> [--part 1|2] [--format text|json|csv] [--input PATH] [--algo NAME|all]
+                                  [--param NAME=VALUE]... [--config PATH]
+    advent-of-code-2022 run --all [--part 1|2] [--format text|json|csv] [--jobs N] [--config PATH]
+    advent-of-code-2022 verify [--answers PATH]
+    advent-of-code-2022 time <day> [--runs N] [--format text|json|csv] [--input PATH] [--algo NAME]
+                                   [--param NAME=VALUE]... [--config PATH]
+    advent-of-code-2022 time --all [--runs N] [--format text|json|csv] [--config PATH]";
+
+pub const ANSWERS_PATH: &str = "answers.txt";
+
+/// Passed to `--algo` to run a day with each of its algorithms and check they agree.
+pub const ALL_ALGORITHMS: &str = "all";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List,
+    Run(RunOptions),
+    Verify(String), // Path of the answers file
+    Time(TimeOptions),
+    Help
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub day: Option<u8>, // None runs every registered day
+    pub part: Option<Part>,
+    pub input: Option<String>,
+    pub format: Format,
+    pub jobs: usize, // Days run at the same time with --all
+    pub algorithm: Option<String>,
+    pub params: Vec<(String, i64)>,
+    pub config: Option<String> // Path of a file of parameters
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TimeOptions {
+    pub day: Option<u8>, // None times every registered day
+    pub input: Option<String>,
+    pub runs: usize,
+    pub format: Format,
+    pub algorithm: Option<String>,
+    pub params: Vec<(String, i64)>,
+    pub config: Option<String> // Path of a file of parameters
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text, // Human-readable; a table for timings
+    Json,
+    Csv
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format: {} (expected text, json or csv)", s))
+        }
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(|arg| arg.as_str()) {
+        None | Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some("list") => {
+            match args.get(1) {
+                None => Ok(Command::List),
+                Some(arg) => Err(format!("Unexpected argument to list: {}", arg))
+            }
+        },
+        Some("run") => parse_run_args(&args[1..]).map(Command::Run),
+        Some("time") => parse_time_args(&args[1..]).map(Command::Time),
+        Some("verify") => {
+            match &args[1..] {
+                [] => Ok(Command::Verify(ANSWERS_PATH.to_string())),
+                [flag, path] if flag == "--answers" => Ok(Command::Verify(path.to_string())),
+                [flag] if flag == "--answers" => Err("--answers needs a value".to_string()),
+                [arg, ..] => Err(format!("Unexpected argument to verify: {}", arg))
+            }
+        },
+        Some(other) => Err(format!("Unknown command: {}", other))
+    }
+}
+
+// What `run` and `time` have in common. The command's own flags, which all take a value, are left in
+// `extra` for it to deal with.
+struct CommonArgs<'a> {
+    all: bool,
+    day: Option<u8>,
+    input: Option<String>,
+    format: Format,
+    algorithm: Option<String>,
+    params: Vec<(String, i64)>,
+    config: Option<String>,
+    extra: Vec<(&'a str, &'a str)>
+}
+
+fn parse_common_args<'a>(command: &str, args: &'a [String], extra: &[&str]) -> Result<CommonArgs<'a>, String> {
+    let mut common = CommonArgs {
+        all: false,
+        day: None,
+        input: None,
+        format: Format::Text,
+        algorithm: None,
+        params: vec![],
+        config: None,
+        extra: vec![]
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().map(|value| value.as_str()).ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--all" => common.all = true,
+            "--format" => common.format = value()?.parse()?,
+            "--input" => common.input = Some(value()?.to_string()),
+            "--algo" => common.algorithm = Some(value()?.to_string()),
+            "--param" => common.params.push(settings::parse_param(value()?)?),
+            "--config" => common.config = Some(value()?.to_string()),
+            flag if extra.contains(&flag) => common.extra.push((flag, value()?)),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            day => {
+                if common.day.is_some() {
+                    return Err(format!("Unexpected argument: {}", day));
+                }
+                let number = day.parse::<u8>().map_err(|_| format!("Invalid day: {}", day))?;
+                common.day = Some(number);
+            }
+        }
+    }
+
+    match (common.all, common.day) {
+        (true, Some(_)) => Err("Pass either a day or --all, not both".to_string()),
+        (false, None) => Err(format!("{} needs a day or --all", command)),
+        (true, None) if common.input.is_some() => Err("--input can only be used with a single day".to_string()),
+        (true, None) if common.algorithm.is_some() => Err("--algo can only be used with a single day".to_string()),
+        (true, None) if !common.params.is_empty() => Err("--param can only be used with a single day".to_string()),
+        _ => Ok(common)
+    }
+}
+
+fn parse_run_args(args: &[String]) -> Result<RunOptions, String> {
+    let common = parse_common_args("run", args, &["--part", "--jobs"])?;
+    let mut part = None;
+    let mut jobs = 1;
+    for (flag, value) in common.extra {
+        match flag {
+            "--part" => part = Some(value.parse()?),
+            _ => jobs = match value.parse() {
+                Ok(jobs) if jobs > 0 => jobs,
+                _ => return Err(format!("Invalid number of jobs: {}", value))
+            }
+        }
+    }
+
+    if jobs != 1 && !common.all {
+        return Err("--jobs can only be used with --all".to_string());
+    }
+    if common.algorithm.as_deref() == Some(ALL_ALGORITHMS) && common.format != Format::Text {
+        return Err("--algo all only works with --format text".to_string());
+    }
+
+    Ok(RunOptions {
+        day: common.day,
+        part,
+        input: common.input,
+        format: common.format,
+        jobs,
+        algorithm: common.algorithm,
+        params: common.params,
+        config: common.config
+    })
+}
+
+fn parse_time_args(args: &[String]) -> Result<TimeOptions, String> {
+    let common = parse_common_args("time", args, &["--runs"])?;
+    let mut runs = 1;
+    for (_, value) in common.extra {
+        runs = match value.parse() {
+            Ok(runs) if runs > 0 => runs,
+            _ => return Err(format!("Invalid number of runs: {}", value))
+        };
+    }
+
+    if common.algorithm.as_deref() == Some(ALL_ALGORITHMS) {
+        return Err("--algo all can only be used with run".to_string());
+    }
+
+    Ok(TimeOptions {
+        day: common.day,
+        input: common.input,
+        runs,
+        format: common.format,
+        algorithm: common.algorithm,
+        params: common.params,
+        config: common.config
+    })
+}
+
+pub fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        Command::List => {
+            for day in days::DAYS.iter() {
+                println!("Day {:02}: {}", day.number, day.title);
+            }
+            Ok(())
+        },
+        Command::Run(options) => run(&options),
+        Command::Verify(path) => verify(&path),
+        Command::Time(options) => time(&options)
+    }
+}
+
+fn run(options: &RunOptions) -> Result<(), String> {
+    let parts = match options.part {
+        None => Part::BOTH.to_vec(),
+        Some(part) => vec![part]
+    };
+
+    let mut results = vec![];
+    let mut report = |number: u8, answers: Vec<(Part, Answer)>| {
+        if options.format == Format::Text {
+            print_answers(number, &answers);
+        }
+        results.push((number, answers));
+    };
+
+    let config = read_config(&options.config)?;
+    let mut failed = vec![];
+    match options.day {
+        Some(number) => {
+            let day = days::get(number).ok_or(format!("Day {} is not solved yet", number))?;
+            let path = options.input.clone().unwrap_or_else(|| day.input_path());
+            let settings = settings_for(day, &options.algorithm, &options.params, &config)?;
+            if options.algorithm.as_deref() == Some(ALL_ALGORITHMS) {
+                return cross_check(day, &path, &parts, &settings);
+            }
+            report(day.number, run_day(day, &path, &parts, &settings)?);
+        },
+        None => {
+            let settings = days::DAYS.iter()
+                .map(|day| settings_for(day, &None, &[], &config))
+                .collect::<Result<Vec<_>, _>>()?;
+            run_all(&parts, options.jobs, &settings, |day, result| match result {
+                Ok(answers) => report(day.number, answers),
+                Err(error) => {
+                    eprintln!("Day {:02} failed: {}", day.number, error);
+                    failed.push(day.number);
+                }
+            });
+        }
+    }
+
+    match options.format {
+        Format::Text => {},
+        Format::Json => print_answers_json(&results),
+        Format::Csv => print_answers_csv(&results)
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} of {} days failed", failed.len(), days::DAYS.len()))
+    }
+}
+
+fn run_day(day: &Day, path: &str, parts: &[Part], settings: &Settings) -> Result<Vec<(Part, Answer)>, String> {
+    let contents = read_file(path)?;
+    day.run(&contents, parts, settings).map_err(|error| error.with_file(path).render(&contents))
+}
+
+fn read_config(path: &Option<String>) -> Result<Vec<ConfigEntry>, String> {
+    let Some(path) = path else { return Ok(vec![]) };
+    let contents = read_file(path)?;
+    settings::parse_config(&contents).map_err(|error| error.with_file(path).render(&contents))
+}
+
+/// Settings for `day`: its parameters from the config file, then those given with `--param`, which win.
+/// Fails if the day doesn't have the algorithm or one of the parameters.
+fn settings_for(day: &Day, algorithm: &Option<String>, params: &[(String, i64)], config: &[ConfigEntry])
+    -> Result<Settings, String> {
+    let mut settings = Settings {
+        algorithm: algorithm.clone().filter(|algorithm| algorithm != ALL_ALGORITHMS),
+        ..Settings::default()
+    };
+    for entry in config.iter().filter(|entry| entry.day == day.number) {
+        settings.params.insert(entry.name.clone(), entry.value);
+    }
+    settings.params.extend(params.iter().cloned());
+
+    day.check(&settings)?;
+    Ok(settings)
+}
+
+/// Runs `day` with each of its algorithms in turn and fails if any of them gets a different answer
+/// from the first.
+fn cross_check(day: &Day, path: &str, parts: &[Part], settings: &Settings) -> Result<(), String> {
+    if day.algorithms.is_empty() {
+        return Err(format!("Day {} has only one algorithm", day.number));
+    }
+
+    let mut results = vec![];
+    for algorithm in day.algorithms {
+        let settings = Settings { algorithm: Some(algorithm.to_string()), ..settings.clone() };
+        let answers = run_day(day, path, parts, &settings)?;
+        println!("Day {:02} using {}", day.number, algorithm);
+        for (part, answer) in &answers {
+            print_answer(*part, answer);
+        }
+        results.push((algorithm, answers));
+    }
+
+    let (first, expected) = &results[0];
+    let mut disagreements = vec![];
+    for (algorithm, answers) in &results[1..] {
+        for ((part, actual), (_, wanted)) in answers.iter().zip(expected) {
+            if actual != wanted {
+                disagreements.push(format!("part {}: {} gives {}, {} gives {}", part, algorithm, actual, first, wanted));
+            }
+        }
+    }
+
+    if disagreements.is_empty() {
+        println!("\nAll {} algorithms agree", results.len());
+        Ok(())
+    } else {
+        Err(format!("Algorithms disagree on {}", disagreements.join("; ")))
+    }
+}
+
+/// Runs every day, with the settings at the same index, on `jobs` threads, handing results to `report`
+/// in day order as soon as they are ready. A day that panics is reported as failed; the others carry on.
+fn run_all<F>(parts: &[Part], jobs: usize, settings: &[Settings], report: F)
+where F: FnMut(&Day, Result<Vec<(Part, Answer)>, String>) {
+    run_in_order(&days::DAYS, jobs, |i, day| run_day(day, &day.input_path(), parts, &settings[i]), report);
+}
+
+// `work` on each item on `jobs` threads, with results reported in the order of `items`. Panics are
+// caught and reported like any other error, and kept off stderr so they don't get ahead of the output.
+fn run_in_order<T, R, W, F>(items: &[T], jobs: usize, work: W, mut report: F)
+where T: Sync, R: Send, W: Fn(usize, &T) -> Result<R, String> + Sync, F: FnMut(&T, Result<R, String>) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    without_panic_messages(|| thread::scope(|scope| {
+        for _ in 0..jobs.min(items.len()) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                let result = catch_panic(|| work(i, item)).and_then(|result| result);
+                if sender.send((i, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut in_order = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&in_order) {
+                report(&items[in_order], result);
+                in_order += 1;
+            }
+        }
+    }));
+}
+
+fn print_answers(number: u8, answers: &[(Part, Answer)]) {
+    println!("Day {:02}", number);
+    for (part, answer) in answers {
+        print_answer(*part, answer);
+    }
+}
+
+// Grids start on a new line so they line up under `Part 2:`
+fn print_answer(part: Part, answer: &Answer) {
+    match answer {
+        Answer::Grid(_) => println!("Part {}:\n{}", part, answer),
+        answer => println!("Part {}: {}", part, answer)
+    }
+}
+
+fn print_answers_json(results: &[(u8, Vec<(Part, Answer)>)]) {
+    let rows: Vec<String> = results.iter()
+        .flat_map(|(number, answers)| answers.iter().map(move |(part, answer)| {
+            format!("  {{\"day\": {}, \"part\": {}, \"type\": {}, \"answer\": {}}}",
+                    number, part, json_string(answer.kind()), answer.to_json())
+        }))
+        .collect();
+
+    println!("[\n{}\n]", rows.join(",\n"));
+}
+
+// Grids go in one field with their rows separated by line breaks; no answer is an empty field.
+fn print_answers_csv(results: &[(u8, Vec<(Part, Answer)>)]) {
+    println!("day,part,type,answer");
+    for (number, answers) in results {
+        for (part, answer) in answers {
+            let text = match answer {
+                Answer::None => String::new(),
+                answer => answer.to_string()
+            };
+            println!("{},{},{},{}", number, part, answer.kind(), csv_field(&text));
+        }
+    }
+}
+
+fn verify(path: &str) -> Result<(), String> {
+    let contents = read_file(path)?;
+    let answers = verify::parse_answers(&contents)
+        .map_err(|error| error.with_file(path).render(&contents))?;
+    let checks = verify::verify(&answers);
+
+    println!("{:<4} {:<5} {:<20} {:<20} {:<20} Status", "Day", "Part", "Input", "Expected", "Actual");
+    for check in checks.iter() {
+        println!("{:<4} {:<5} {:<20} {:<20} {:<20} {}",
+                 format!("{:02}", check.day),
+                 check.part.to_string(),
+                 check.input,
+                 cell(&check.expected),
+                 cell(&check.actual),
+                 check.status);
+    }
+
+    let count = |status: fn(&Check) -> bool| checks.iter().filter(|check| status(check)).count();
+    let passed = count(|check| check.status == verify::Status::Pass);
+    let failed = count(Check::failed);
+    let missing = count(|check| check.status == verify::Status::Missing);
+    let skipped = count(|check| check.status == verify::Status::NoInput);
+    println!("\n{} passed, {} failed, {} missing, {} without input", passed, failed, missing, skipped);
+
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{} of {} checks failed", failed, checks.len()))
+    }
+}
+
+// Answers are shown on one line and cut short so the table stays readable; day 10's screen is long.
+fn cell(answer: &Option<String>) -> String {
+    let answer = match answer {
+        None => return "-".to_string(),
+        Some(answer) => answer.replace('\n', "\\n")
+    };
+    if answer.chars().count() > 20 {
+        format!("{}...", answer.chars().take(17).collect::<String>())
+    } else {
+        answer
+    }
+}
+
+fn time(options: &TimeOptions) -> Result<(), String> {
+    let config = read_config(&options.config)?;
+    let mut timings = vec![];
+    match options.day {
+        Some(number) => {
+            let day = days::get(number).ok_or(format!("Day {} is not solved yet", number))?;
+            let path = options.input.clone().unwrap_or_else(|| day.input_path());
+            let settings = settings_for(day, &options.algorithm, &options.params, &config)?;
+            timings.push((day.number, time_day(day, &path, options.runs, &settings)?));
+        },
+        None => {
+            for day in days::DAYS.iter() {
+                let settings = settings_for(day, &None, &[], &config)?;
+                let timing = without_panic_messages(|| catch_panic(|| time_day(day, &day.input_path(), options.runs, &settings)));
+                match timing.and_then(|result| result) {
+                    Ok(timing) => timings.push((day.number, timing)),
+                    Err(error) => eprintln!("Skipping day {:02}: {}", day.number, error)
+                }
+            }
+        }
+    }
+
+    match options.format {
+        Format::Text => print_timing_table(&timings),
+        Format::Json => print_timing_json(&timings),
+        Format::Csv => print_timing_csv(&timings)
+    }
+
+    Ok(())
+}
+
+fn time_day(day: &Day, path: &str, runs: usize, settings: &Settings) -> Result<Timing, String> {
+    let contents = read_file(path)?;
+    day.time(&contents, runs, settings).map_err(|error| error.with_file(path).render(&contents))
+}
+
+fn print_timing_table(timings: &[(u8, Timing)]) {
+    let stats = |stats: &Stats| format!("{} ±{} (min {})", Pretty(stats.mean), Pretty(stats.stddev), Pretty(stats.min));
+
+    println!("{:<4} {:<32} {:<32} {:<32} Total", "Day", "Parse", "Part 1", "Part 2");
+    for (number, timing) in timings {
+        println!("{:<4} {:<32} {:<32} {:<32} {}",
+                 format!("{:02}", number),
+                 stats(&timing.parse),
+                 stats(&timing.part1),
+                 stats(&timing.part2),
+                 Pretty(timing.total()));
+    }
+
+    let total = timings.iter().map(|(_, timing)| timing.total()).sum();
+    println!("\nAll days: {}", Pretty(total));
+}
+
+// Hand-written to avoid pulling in a JSON library; every value is a number, so no escaping is needed.
+fn print_timing_json(timings: &[(u8, Timing)]) {
+    let stats = |stats: &Stats| format!("{{\"min_ns\": {}, \"mean_ns\": {}, \"stddev_ns\": {}}}",
+                                        stats.min.as_nanos(), stats.mean.as_nanos(), stats.stddev.as_nanos());
+
+    let days: Vec<String> = timings.iter().map(|(number, timing)| {
+        format!("  {{\"day\": {}, \"runs\": {}, \"parse\": {}, \"part1\": {}, \"part2\": {}, \"total_ns\": {}}}",
+                number, timing.runs, stats(&timing.parse), stats(&timing.part1), stats(&timing.part2),
+                timing.total().as_nanos())
+    }).collect();
+
+    println!("[\n{}\n]", days.join(",\n"));
+}
+
+fn print_timing_csv(timings: &[(u8, Timing)]) {
+    println!("day,runs,step,min_ns,mean_ns,stddev_ns");
+    for (number, timing) in timings {
+        for (step, stats) in [("parse", &timing.parse), ("part1", &timing.part1), ("part2", &timing.part2)] {
+            println!("{},{},{},{},{},{}", number, timing.runs, step,
+                     stats.min.as_nanos(), stats.mean.as_nanos(), stats.stddev.as_nanos());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(&args.split_whitespace().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn run_and_time_options() {
+        assert_eq!(parse("run 12 --part 2 --format json --algo bfs --param row=3 --config a.cfg"), Ok(Command::Run(RunOptions {
+            day: Some(12),
+            part: Some(Part::Two),
+            input: None,
+            format: Format::Json,
+            jobs: 1,
+            algorithm: Some("bfs".to_string()),
+            params: vec![("row".to_string(), 3)],
+            config: Some("a.cfg".to_string())
+        })));
+        assert_eq!(parse("time --all --runs 5 --format csv"), Ok(Command::Time(TimeOptions {
+            day: None,
+            input: None,
+            runs: 5,
+            format: Format::Csv,
+            algorithm: None,
+            params: vec![],
+            config: None
+        })));
+        assert!(matches!(parse("run --all --jobs 4"), Ok(Command::Run(RunOptions { jobs: 4, .. }))));
+        assert!(matches!(parse("run 12 --algo all"), Ok(Command::Run(_))));
+    }
+
+    #[test]
+    fn invalid_options() {
+        let cases = [
+            ("run", "run needs a day or --all"),
+            ("time", "time needs a day or --all"),
+            ("run 1 --all", "Pass either a day or --all, not both"),
+            ("run 1 2", "Unexpected argument: 2"),
+            ("run 12 --jobs 2", "--jobs can only be used with --all"),
+            ("run --all --jobs 0", "Invalid number of jobs: 0"),
+            ("time --all --runs x", "Invalid number of runs: x"),
+            ("run --all --input a.txt", "--input can only be used with a single day"),
+            ("time --all --algo bfs", "--algo can only be used with a single day"),
+            ("run --all --param row=1", "--param can only be used with a single day"),
+            ("run 12 --algo all --format json", "--algo all only works with --format text"),
+            ("time 12 --algo all", "--algo all can only be used with run"),
+            ("time 12 --part 1", "Unknown option: --part"),
+            ("run 12 --part", "--part needs a value"),
+            ("run 12 --param row", "Expected NAME=VALUE, found row")
+        ];
+        for (args, expected) in cases {
+            assert_eq!(parse(args), Err(expected.to_string()), "{}", args);
+        }
+    }
+
+    #[test]
+    fn panicking_item_does_not_stop_the_rest() {
+        for jobs in [1, 3] {
+            let mut reported = vec![];
+            run_in_order(&[1, 2, 3, 4], jobs, |_, &n| match n {
+                2 => panic!("item {} is broken", n),
+                3 => Err("no input".to_string()),
+                n => Ok(n * 10)
+            }, |&n, result| reported.push((n, result)));
+
+            assert_eq!(reported, vec![
+                (1, Ok(10)),
+                (2, Err("panicked: item 2 is broken".to_string())),
+                (3, Err("no input".to_string())),
+                (4, Ok(40))
+            ]);
+        }
+    }
+}
@@ -1,49 +1,96 @@
-use std::fs::read_to_string;
-
-fn read_input_day_01() -> Vec<Vec<i32>> {
-    let contents = match read_to_string("inputs/day01.txt") {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    let lines = contents.split("\r\n");
-
-    let mut result: Vec<Vec<i32>> = Vec::new();
-    let mut vec: Vec<i32> = Vec::new();
-    for line in lines {
-        if line.len() > 0 {
-            vec.push(line.parse::<i32>().unwrap());
-        } else {
-            result.push(vec.clone());
-            vec.clear();
-        }
-    }
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{parse_blocks, parse_number};
+use super::Solution;
+
+pub struct Day01;
 
-    result
+pub fn read_input_day_01(contents: &str) -> Result<Vec<Vec<i32>>, ParseError> {
+    let elves: Vec<Vec<i32>> = parse_blocks(contents, |elf| {
+        elf.iter().enumerate().map(|(i, line)| {
+            parse_number(line, line).map_err(|error| error.offset(i))
+        }).collect()
+    })?;
+
+    // Part 2 adds up the top three
+    if elves.len() < 3 {
+        return Err(ParseError::end_of_input(contents, "at least 3 elves"));
+    }
+    Ok(elves)
 }
 
-pub fn solve() {
-    let input = read_input_day_01();
-    let mut calories = get_calories(input);
-    calories.sort();
-    let part1 = day_01_part1(&calories);
-    let part2 = day_01_part2(&calories);
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let mut calories = get_calories(read_input_day_01(contents)?);
+        calories.sort();
+        Ok(calories)
+    }
+
+    fn part1(calories: &Self::Input) -> i32 {
+        day_01_part1(calories)
+    }
 
-    println!("Day 01");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn part2(calories: &Self::Input) -> i32 {
+        day_01_part2(calories)
+    }
 }
 
-fn get_calories(input: Vec<Vec<i32>>) -> Vec<i32> {
+pub fn get_calories(input: Vec<Vec<i32>>) -> Vec<i32> {
     input.into_iter().map(|elf| {
         elf.into_iter().reduce(|total, calories| { total + calories }).unwrap()
     }).collect()
 }
 
-fn day_01_part1(calories: &Vec<i32>) -> i32 {
-    *calories.into_iter().max().unwrap()
+pub fn day_01_part1(calories: &[i32]) -> i32 {
+    *calories.iter().max().unwrap()
 }
 
-fn day_01_part2(calories: &Vec<i32>) -> i32 {
+pub fn day_01_part2(calories: &[i32]) -> i32 {
     let len = calories.len();
     calories[len - 1] + calories[len - 2] + calories[len - 3]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day01::parse(EXAMPLE).unwrap();
+        assert_eq!(Day01::part1(&input), 24000);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day01::parse(EXAMPLE).unwrap();
+        assert_eq!(Day01::part2(&input), 45000);
+    }
+
+    #[test]
+    fn too_few_elves() {
+        for contents in ["", "1000\n\n2000\n"] {
+            let error = Day01::parse(contents).unwrap_err();
+            assert_eq!((error.expected.as_str(), error.found.as_str()), ("at least 3 elves", "end of input"));
+        }
+    }
+}
@@ -1,43 +1,45 @@
-use std::fs::read_to_string;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::parse_lines;
+use super::Solution;
 
-fn read_input() -> Vec<(i32, i32)> {
-    let contents = match read_to_string("inputs/day02.txt") {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    let lines = contents.split("\r\n");
+pub struct Day02;
 
-    let mut result: Vec<(i32, i32)> = Vec::new();
-    for line in lines {
-        if line.is_empty() {continue};
-        let pair_raw: Vec<&str> = line.split(" ").collect();
-        let pair = (map_move(pair_raw[0]), map_move(pair_raw[1]));
-        result.push(pair);
-    }
-
-    result
+pub fn read_input(contents: &str) -> Result<Vec<(i32, i32)>, ParseError> {
+    parse_lines(contents, |line| {
+        let (theirs, ours) = line.split_once(' ')
+            .ok_or_else(|| ParseError::at(line, &line[line.len()..], "two moves separated by a space"))?;
+        Ok((map_move(line, theirs, "A, B or C")?, map_move(line, ours, "X, Y or Z")?))
+    })
 }
 
-fn map_move(mov: &str) -> i32 {
+fn map_move(line: &str, mov: &str, expected: &str) -> Result<i32, ParseError> {
     match mov {
-        "A" | "X" => 0,
-        "B" | "Y" => 1,
-        "C" | "Z" => 2,
-        _ => panic!("Unexpected move: {}", mov)
+        "A" | "X" => Ok(0),
+        "B" | "Y" => Ok(1),
+        "C" | "Z" => Ok(2),
+        _ => Err(ParseError::at(line, mov, expected))
     }
 }
 
-pub fn solve() {
-    let input = read_input();
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
+impl Solution for Day02 {
+    type Input = Vec<(i32, i32)>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
 
-    println!("Day 02");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn part1(moves: &Self::Input) -> i32 {
+        solve_part1(moves)
+    }
+
+    fn part2(guides: &Self::Input) -> i32 {
+        solve_part2(guides)
+    }
 }
 
-fn solve_part1(moves: &Vec<(i32, i32)>) -> i32 {
+pub fn solve_part1(moves: &[(i32, i32)]) -> i32 {
     let mut score = 0;
     for mov in moves {
         let round_score = get_score(mov);
@@ -46,7 +48,7 @@ fn solve_part1(moves: &Vec<(i32, i32)>) -> i32 {
     score
 }
 
-fn solve_part2(guides: &Vec<(i32, i32)>) -> i32 {
+pub fn solve_part2(guides: &[(i32, i32)]) -> i32 {
     let mut score = 0;
     for guide in guides {
         let my_move = get_move(guide);
@@ -74,3 +76,26 @@ fn get_move(guide: &(i32, i32)) -> i32 {
         _ => panic!("Unknown strategy {}", guide.1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+A Y
+B X
+C Z
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day02::parse(EXAMPLE).unwrap();
+        assert_eq!(Day02::part1(&input), 15);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day02::parse(EXAMPLE).unwrap();
+        assert_eq!(Day02::part2(&input), 12);
+    }
+}
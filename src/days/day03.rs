@@ -1,40 +1,45 @@
 use std::collections::{HashMap, HashSet};
-use std::fs::{File};
-use std::io::{BufRead, BufReader};
-
-fn read_input() -> Vec<String> {
-    let input = match File::open("inputs/day03.txt") {
-        Ok(contents) => contents,
-        Err(err) => panic!("Can't open file: {:?}", err)
-    };
-    let reader = BufReader::new(input);
-
-    let mut result: Vec<String> = Vec::new();
-    for line_maybe in reader.lines() {
-        match line_maybe {
-            Ok(line) => result.push(line),
-            Err(err) => panic!("Can't read line: {:?}", err)
-        }
-    }
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::parse_lines;
+use super::Solution;
 
-    result
+pub struct Day03;
+
+pub fn read_input(contents: &str) -> Result<Vec<String>, ParseError> {
+    parse_lines(contents, |line| {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::at(line, &line[i..i + c.len_utf8()], "an item letter (a-z or A-Z)"));
+        }
+        if line.len() % 2 != 0 {
+            return Err(ParseError::at(line, &line[line.len()..], "an even number of items"));
+        }
+        Ok(line.to_string())
+    })
 }
 
-pub fn solve() {
-    let input = read_input();
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
+impl Solution for Day03 {
+    type Input = Vec<String>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> u32 {
+        solve_part1(rucksacks)
+    }
 
-    println!("Day 03");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn part2(rucksacks: &Self::Input) -> u32 {
+        solve_part2(rucksacks)
+    }
 }
 
-fn solve_part1(rucksacks: &Vec<String>) -> u32 {
+pub fn solve_part1(rucksacks: &[String]) -> u32 {
     rucksacks.iter().map(|rs| {get_priority(rs)}).sum()
 }
 
-fn get_priority(rucksack: &String) -> u32 {
+fn get_priority(rucksack: &str) -> u32 {
     let (compartment_a, compartment_b) = rucksack.split_at(rucksack.len() / 2);
     let shared_letter = get_shared_letter(compartment_a, compartment_b);
     get_letter_priority(shared_letter)
@@ -63,27 +68,24 @@ fn get_shared_letter(c1: &str, c2: &str) -> char {
     panic!("Compartments don't share items: {} / {}", c1, c2);
 }
 
-fn solve_part2(rucksacks: &Vec<String>) -> u32 {
+pub fn solve_part2(rucksacks: &[String]) -> u32 {
     let mut count: HashMap<char, u32> = HashMap::new();
-    let mut i = 0;
     let mut sum = 0;
     let mut badge;
 
-    for rucksack in rucksacks {
+    for (i, rucksack) in rucksacks.iter().enumerate() {
         badge = count_letters(rucksack, &mut count);
 
         if i % 3 == 2 {
             count.clear();
             sum += get_letter_priority(badge);
         }
-
-        i += 1;
     }
 
     sum
 }
 
-fn count_letters(rucksack: &String, map: &mut HashMap<char, u32>) -> char {
+fn count_letters(rucksack: &str, map: &mut HashMap<char, u32>) -> char {
     let mut max_key = '?';
     let mut max_val = 0;
     let mut already_counted: HashSet<char> = HashSet::new();
@@ -110,3 +112,29 @@ fn count_letters(rucksack: &String, map: &mut HashMap<char, u32>) -> char {
 
     max_key
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day03::parse(EXAMPLE).unwrap();
+        assert_eq!(Day03::part1(&input), 157);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day03::parse(EXAMPLE).unwrap();
+        assert_eq!(Day03::part2(&input), 70);
+    }
+}
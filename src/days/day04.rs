@@ -1,45 +1,86 @@
-use super::super::utility::utils::parse_file;
+use super::super::utility::intervals::IntervalSet;
+use super::super::utility::parse_error::ParseError;
+use super::super::utility::utils::{parse_lines, parse_number};
+use super::Solution;
 
-type Range = (i32, i32);
+pub struct Day04;
 
-fn read_input() -> Vec<(Range, Range)> {
-    parse_file("inputs/day04.txt", |line| {
-        let elves: Vec<&str> = line.split(",").collect();
-        (parse_range(elves[0]), parse_range(elves[1]))
+pub type Range = (i32, i32);
+
+pub fn read_input(contents: &str) -> Result<Vec<(Range, Range)>, ParseError> {
+    parse_lines(contents, |line| {
+        let (first, second) = line.split_once(',')
+            .ok_or_else(|| ParseError::at(line, line, "two ranges separated by a comma"))?;
+        Ok((parse_range(line, first)?, parse_range(line, second)?))
     })
 }
 
-fn parse_range(text: &str) -> Range {
-    let pair: Vec<&str> =  text.split("-").collect();
-    return (pair[0].parse::<i32>().unwrap(), pair[1].parse::<i32>().unwrap());
+fn parse_range(line: &str, text: &str) -> Result<Range, ParseError> {
+    let (start, end) = text.split_once('-')
+        .ok_or_else(|| ParseError::at(line, text, "a range like 2-4"))?;
+    Ok((parse_number(line, start)?, parse_number(line, end)?))
 }
 
 fn is_subrange(range: Range, candidate: Range) -> bool {
-    candidate.0 >= range.0 && candidate.1 <= range.1
+    IntervalSet::from(candidate).is_subset(&IntervalSet::from(range))
 }
 
 fn overlaps(a: Range, b: Range) -> bool {
-    !(a.1 < b.0 || b.1 < a.0)
+    !IntervalSet::from(a).intersection(&IntervalSet::from(b)).is_empty()
 }
 
-pub fn solve() {
-    let input = read_input();
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
+impl Solution for Day04 {
+    type Input = Vec<(Range, Range)>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
 
-    println!("Day 04");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn part1(assignments: &Self::Input) -> usize {
+        solve_part1(assignments)
+    }
+
+    fn part2(assignments: &Self::Input) -> usize {
+        solve_part2(assignments)
+    }
 }
 
-fn solve_part1(assignments: &Vec<(Range, Range)>) -> usize {
+pub fn solve_part1(assignments: &[(Range, Range)]) -> usize {
     assignments.iter()
         .filter(|assignment| is_subrange(assignment.0, assignment.1) || is_subrange(assignment.1, assignment.0))
         .count()
 }
 
-fn solve_part2(assignments: &Vec<(Range, Range)>) -> usize {
+pub fn solve_part2(assignments: &[(Range, Range)]) -> usize {
     assignments.iter()
         .filter(|assignment| overlaps(assignment.0, assignment.1))
         .count()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day04::parse(EXAMPLE).unwrap();
+        assert_eq!(Day04::part1(&input), 2);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day04::parse(EXAMPLE).unwrap();
+        assert_eq!(Day04::part2(&input), 4);
+    }
+}
@@ -1,43 +1,36 @@
-use std::fs::read_to_string;
 use regex::{Captures, Regex};
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{numbered_lines, parse_number};
+use super::Solution;
+
+pub struct Day05;
 
 #[derive(Debug)]
 #[derive(Clone)]
-struct StackGame {
+pub struct StackGame {
     stacks: Vec<Vec<String>>,
     instructions: Vec<(usize, usize, usize)>
 }
 
-fn read_input() -> StackGame {
+pub fn read_input(contents: &str) -> Result<StackGame, ParseError> {
     let stack_rgx = Regex::new(r"\[(\w)]|\s(\s{3})").unwrap();
-    let move_rgx = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
 
     let mut captures: Vec<Captures> = Vec::new();
 
-    let contents = match read_to_string("inputs/day05.txt") {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    let lines = contents.split("\r\n");
-    
     let mut result = StackGame {
         stacks: Vec::new(),
         instructions: Vec::new()
     };
-    for line in lines {
+    for (i, line) in numbered_lines(contents) {
         if line.starts_with(" 1") {
-            let stack_amount = line.split_whitespace().last().unwrap().parse::<usize>().unwrap();
+            let last = line.split_whitespace().last().unwrap();
+            let stack_amount = parse_number(line, last).map_err(|error| error.offset(i))?;
             captures.reverse();
             add_stacks(&captures, &mut result, stack_amount);
             captures.clear();
         } else if line.starts_with("move") {
-            for c in move_rgx.captures_iter(line) {
-                captures.push(c);
-            }
-        } else if line.is_empty() {
-            if !captures.is_empty() {
-                add_moves(&captures, &mut result);
-            }
+            let instruction = parse_move(line, result.stacks.len()).map_err(|error| error.offset(i))?;
+            result.instructions.push(instruction);
         } else {
             for c in stack_rgx.captures_iter(line) {
                 captures.push(c);
@@ -45,80 +38,151 @@ fn read_input() -> StackGame {
         }
     }
     
-    result
+    Ok(result)
 }
 
-fn add_stacks(captures: &Vec<Captures>, stack_game: &mut StackGame, stack_amount: usize) {
+fn add_stacks(captures: &[Captures], stack_game: &mut StackGame, stack_amount: usize) {
     for _ in 0..stack_amount {
         stack_game.stacks.push(Vec::new());
     }
     
-    let mut si = 0;
-    for c in captures {
+    for (si, c) in captures.iter().enumerate() {
         let m = c.get(1).map_or("", |m| m.as_str());
         if !m.is_empty() {
             stack_game.stacks.get_mut(stack_amount - 1 - (si % stack_amount)).unwrap().push(m.to_string())
         }
-        
-        si += 1;
     }
 }
 
-fn add_moves(captures: &Vec<Captures>, stack_game: &mut StackGame) {
-    for c in captures {
-        let x1 = c.get(1).unwrap().as_str().parse::<usize>().unwrap();
-        let x2 = c.get(2).unwrap().as_str().parse::<usize>().unwrap();
-        let x3 = c.get(3).unwrap().as_str().parse::<usize>().unwrap();
-        stack_game.instructions.push((x1, x2, x3));
+// `move <amount> from <stack> to <stack>`, with each word checked on its own so errors point at it
+fn parse_move(line: &str, stack_amount: usize) -> Result<(usize, usize, usize), ParseError> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let word = |i: usize, expected: &str| words.get(i).copied()
+        .ok_or_else(|| ParseError::at(line, &line[line.len()..], expected));
+    let keyword = |i: usize, keyword: &str| match word(i, keyword)? {
+        text if text == keyword => Ok(()),
+        text => Err(ParseError::at(line, text, keyword))
+    };
+    let stack = |i: usize| {
+        let text = word(i, "a stack")?;
+        match parse_number(line, text)? {
+            n if n >= 1 && n <= stack_amount => Ok(n),
+            _ => Err(ParseError::at(line, text, format!("a stack between 1 and {}", stack_amount)))
+        }
+    };
+
+    keyword(0, "move")?;
+    let x1 = parse_number(line, word(1, "an amount")?)?;
+    keyword(2, "from")?;
+    let x2 = stack(3)?;
+    keyword(4, "to")?;
+    let x3 = stack(5)?;
+    if let Some(extra) = words.get(6) {
+        return Err(ParseError::at(line, extra, "end of line"));
     }
+    Ok((x1, x2, x3))
 }
 
-pub fn solve() {
-    let mut input = read_input();
+impl Solution for Day05 {
+    type Input = StackGame;
+    type Part1 = String;
+    type Part2 = String;
 
-    let part1 = solve_part1(&mut input.clone());
-    let part2 = solve_part2(&mut input);
-    
-    println!("Day 05");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
+
+    fn part1(stack_game: &Self::Input) -> String {
+        solve_part1(&mut stack_game.clone())
+    }
+
+    fn part2(stack_game: &Self::Input) -> String {
+        solve_part2(&mut stack_game.clone())
+    }
 }
 
-fn solve_part1(stack_game: &mut StackGame) -> String {
+// A move takes as many crates as the stack has, up to its amount
+pub fn solve_part1(stack_game: &mut StackGame) -> String {
     for ins in stack_game.instructions.iter() {
         let (amount, from, to) = *ins;
         for _ in 0..amount {
-            let crt = stack_game.stacks.get_mut(from - 1).unwrap().pop().unwrap();
-            stack_game.stacks.get_mut(to - 1).unwrap().push(crt);
+            let Some(crt) = stack_game.stacks[from - 1].pop() else { break };
+            stack_game.stacks[to - 1].push(crt);
         }
     }
-    
-    let mut result= String::new();
-    for stack in stack_game.stacks.iter() {
-        result.push_str(stack.last().unwrap());
-    }
-    
-    result
+
+    top_crates(stack_game)
 }
 
-fn solve_part2(stack_game: &mut StackGame) -> String {
+// Empty stacks have nothing on top, so they are left out
+fn top_crates(stack_game: &StackGame) -> String {
+    stack_game.stacks.iter().filter_map(|stack| stack.last()).map(String::as_str).collect()
+}
+
+pub fn solve_part2(stack_game: &mut StackGame) -> String {
     let mut temp_stack: Vec<String> = Vec::new();
     for ins in stack_game.instructions.iter() {
         let (amount, from, to) = *ins;
         for _ in 0..amount {
-            let crt = stack_game.stacks.get_mut(from - 1).unwrap().pop().unwrap();
+            let Some(crt) = stack_game.stacks[from - 1].pop() else { break };
             temp_stack.push(crt);
         }
-        while !temp_stack.is_empty() {
-            let crt = temp_stack.pop().unwrap();
+        while let Some(crt) = temp_stack.pop() {
             stack_game.stacks.get_mut(to - 1).unwrap().push(crt);
         }
     }
 
-    let mut result= String::new();
-    for stack in stack_game.stacks.iter() {
-        result.push_str(stack.last().unwrap());
+    top_crates(stack_game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day05::parse(EXAMPLE).unwrap();
+        assert_eq!(Day05::part1(&input), "CMZ");
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day05::parse(EXAMPLE).unwrap();
+        assert_eq!(Day05::part2(&input), "MCD");
     }
 
-    result
+    #[test]
+    fn errors_point_at_the_bad_word() {
+        // (move, column, expected)
+        let cases = [
+            ("move 1 from x to 3", 13, "a number"),
+            ("move 1 from 2 to 4", 18, "a stack between 1 and 3"),
+            ("move 1 fro 2 to 3", 8, "from"),
+            ("move 1 from 2", 14, "to"),
+            ("move 1 from 2 to 3 now", 20, "end of line")
+        ];
+        for (line, column, expected) in cases {
+            let error = Day05::parse(&EXAMPLE.replace("move 1 from 1 to 2", line)).unwrap_err();
+            assert_eq!((error.line, error.column, error.expected.as_str()), (10, column, expected), "{}", line);
+        }
+    }
+
+    #[test]
+    fn empty_stacks() {
+        let input = Day05::parse(&EXAMPLE.replace("move 1 from 1 to 2", "move 5 from 1 to 2")).unwrap();
+        assert_eq!(Day05::part1(&input), "CZ");
+        assert_eq!(Day05::part2(&input), "CD");
+    }
 }
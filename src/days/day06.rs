@@ -1,45 +1,117 @@
 use std::collections::HashSet;
-use std::fs::read_to_string;
-
-fn read_input() -> String {
-    let contents = match read_to_string("inputs/day06.txt") {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    
-    contents.to_string()
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::whole;
+use super::Solution;
+
+pub struct Day06;
+
+// The longest marker part 2 looks for
+const LONGEST_MARKER: usize = 14;
+
+// `find_marker` works on bytes, so the datastream has to be a single line of ASCII letters
+pub fn read_input(contents: &str) -> Result<String, ParseError> {
+    let mut lines = whole(contents).lines();
+    let data = lines.next().unwrap_or("");
+    if let Some((i, c)) = data.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::at(data, &data[i..i + c.len_utf8()], "a letter"));
+    }
+    if let Some(line) = lines.next() {
+        return Err(ParseError::at(line, line, "end of input").offset(1));
+    }
+    if data.len() < LONGEST_MARKER {
+        return Err(ParseError::end_of_input(contents, format!("at least {} letters", LONGEST_MARKER)));
+    }
+    Ok(data.to_string())
 }
 
-pub fn solve() {
-    let input = read_input();
+impl Solution for Day06 {
+    type Input = String;
+    type Part1 = Option<usize>;
+    type Part2 = Option<usize>;
 
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
 
-    println!("Day 06");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn part1(data: &Self::Input) -> Option<usize> {
+        solve_part1(data)
+    }
+
+    fn part2(data: &Self::Input) -> Option<usize> {
+        solve_part2(data)
+    }
 }
 
-fn find_marker(data: &String, distinct: usize) -> usize {
-    let mut i = 0;
+// Characters read up to the end of the first `distinct` different ones in a row, if there are any
+fn find_marker(data: &str, distinct: usize) -> Option<usize> {
     let mut chars = HashSet::new();
-    while chars.len() != distinct {
+    for i in 0..=data.len().checked_sub(distinct)? {
         let sliding_window = &data[i..i+distinct];
         chars.clear();
         for c in sliding_window.chars() {
             chars.insert(c);
         }
-        i += 1
+        if chars.len() == distinct {
+            return Some(i + distinct);
+        }
     }
 
-    i - 1 + distinct
+    None
 }
 
-fn solve_part1(data: &String) -> usize {
+pub fn solve_part1(data: &str) -> Option<usize> {
     find_marker(data, 4)
 }
 
-fn solve_part2(data: &String) -> usize {
-    find_marker(data, 14)
+pub fn solve_part2(data: &str) -> Option<usize> {
+    find_marker(data, LONGEST_MARKER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each example with its part 1 and part 2 answer
+    const EXAMPLES: [(&str, usize, usize); 4] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26)
+    ];
+
+    #[test]
+    fn part1_examples() {
+        for (example, expected, _) in EXAMPLES {
+            let input = Day06::parse(example).unwrap();
+            assert_eq!(Day06::part1(&input), Some(expected), "{}", example);
+        }
+    }
+
+    #[test]
+    fn part2_examples() {
+        for (example, _, expected) in EXAMPLES {
+            let input = Day06::parse(example).unwrap();
+            assert_eq!(Day06::part2(&input), Some(expected), "{}", example);
+        }
+    }
+
+    #[test]
+    fn no_marker() {
+        let input = Day06::parse(&"a".repeat(20)).unwrap();
+        assert_eq!((Day06::part1(&input), Day06::part2(&input)), (None, None));
+        assert_eq!(solve_part1("abcd"), Some(4));
+        assert_eq!(solve_part1("abc"), None);
+    }
+
+    #[test]
+    fn rejects_short_or_non_letter_datastreams() {
+        let error = Day06::parse("").unwrap_err();
+        assert_eq!(error.expected, "at least 14 letters");
+        assert!(Day06::parse("abcdefghijklm").is_err());
+
+        let error = Day06::parse("abcdéfghijklmnop").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (1, 5, "é"));
+        let error = Day06::parse("abcdefghijklmnop\nabc").unwrap_err();
+        assert_eq!((error.line, error.found.as_str()), (2, "abc"));
+    }
 }
@@ -1,9 +1,16 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::utility::utils::parse_file;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{parse_lines, parse_number};
+use super::Solution;
+
+pub struct Day07;
+
+pub const DISK_SIZE: i32 = 70_000_000;
+pub const UPDATE_SIZE: i32 = 30_000_000;
 
 #[derive(Debug)]
-enum Command {
+pub enum Command {
     ChangeDir(String),
     IsFile(i32),
     IsDirectory(String),
@@ -20,7 +27,7 @@ pub enum FileSystemItem {
 
 #[derive(Debug)]
 #[derive(PartialEq)]
-struct TreeNode {
+pub struct TreeNode {
     label: FileSystemItem,
     children: Vec<Rc<RefCell<TreeNode>>>,
     parent: Option<Rc<RefCell<TreeNode>>>
@@ -28,7 +35,7 @@ struct TreeNode {
 
 impl TreeNode {
     pub fn new(label: FileSystemItem) -> TreeNode {
-        return TreeNode {
+        TreeNode {
             label,
             children: vec![],
             parent: None
@@ -40,32 +47,26 @@ impl TreeNode {
     }
 }
 
-fn read_input() -> Vec<Command> {
-    parse_file("inputs/day07.txt", |line| {
-        if line.starts_with("$ cd") {
-            let parts = line.split_at(5);
-            Command::ChangeDir(parts.1.to_string())
-        } else if line.starts_with("dir") {
-            let parts = line.split_at(4);
-            Command::IsDirectory(parts.1.to_string())
-        } else if line.starts_with("$ ls") {
-            Command::NoOp
-        } else if !line.is_empty() {
-            let parts = line.split_once(" ");
-            match parts {
-                None => panic!("Malformed line: {}", line),
-                Some((size, _)) => {
-                    let size_num: i32 = size.parse().unwrap();
-                    Command::IsFile(size_num)
-                }
-            }
+pub fn read_input(contents: &str) -> Result<Vec<Command>, ParseError> {
+    parse_lines(contents, |line| {
+        if let Some(dir) = line.strip_prefix("$ cd ") {
+            Ok(Command::ChangeDir(dir.to_string()))
+        } else if let Some(dir) = line.strip_prefix("dir ") {
+            Ok(Command::IsDirectory(dir.to_string()))
+        } else if line == "$ ls" {
+            Ok(Command::NoOp)
+        } else if line.starts_with('$') {
+            Err(ParseError::at(line, line, "$ cd <dir> or $ ls"))
         } else {
-            Command::NoOp
+            match line.split_once(' ') {
+                None => Err(ParseError::at(line, line, "dir <name> or <size> <name>")),
+                Some((size, _)) => Ok(Command::IsFile(parse_number(line, size)?))
+            }
         }
     })
 }
 
-fn build_fsystem(cmds: &Vec<Command>) -> Rc<RefCell<TreeNode>> {
+pub fn build_fsystem(cmds: &[Command]) -> Rc<RefCell<TreeNode>> {
     let mut current = Rc::new(RefCell::new(TreeNode::new(FileSystemItem::Root)));
     //current.borrow_mut().label = Some(FileSystemItem::Root);
 
@@ -132,39 +133,88 @@ fn build_fsystem(cmds: &Vec<Command>) -> Rc<RefCell<TreeNode>> {
     current
 }
 
-fn get_size(tree: &Rc<RefCell<TreeNode>>, mut sizes: &mut Vec<i32>) -> i32 {
+fn get_size(tree: &Rc<RefCell<TreeNode>>, sizes: &mut Vec<i32>) -> i32 {
     let b = tree.borrow_mut();
     match b.label {
         FileSystemItem::File(size) => size,
         _ => {
-            let size = b.children.iter().map(|c| get_size(c, &mut sizes)).sum();
+            let size = b.children.iter().map(|c| get_size(c, sizes)).sum();
             sizes.push(size);
             size
         }
     }
 }
 
-pub fn solve() {
-    let input = read_input();
-    let fsystem = build_fsystem(&input);
+impl Solution for Day07 {
+    type Input = Rc<RefCell<TreeNode>>;
+    type Part1 = i32;
+    type Part2 = i32;
 
-    let part1 = solve_part1(&fsystem);
-    let part2 = solve_part2(&fsystem);
-    
-    println!("Day 07");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        Ok(build_fsystem(&read_input(contents)?))
+    }
+
+    fn part1(fsys: &Self::Input) -> i32 {
+        solve_part1(fsys)
+    }
+
+    fn part2(fsys: &Self::Input) -> i32 {
+        solve_part2(fsys, DISK_SIZE, UPDATE_SIZE)
+    }
 }
 
-fn solve_part1(fsys: &Rc<RefCell<TreeNode>>) -> i32 {
+pub fn solve_part1(fsys: &Rc<RefCell<TreeNode>>) -> i32 {
     let mut sizes = Vec::new();
     get_size(fsys, &mut sizes);
     sizes.into_iter().filter(|size| *size <= 100000).sum()
 }
 
-fn solve_part2(fsys: &Rc<RefCell<TreeNode>>) -> i32 {
+pub fn solve_part2(fsys: &Rc<RefCell<TreeNode>>, disk_size: i32, update_size: i32) -> i32 {
     let mut sizes = Vec::new();
-    let unused = 70_000_000 - get_size(fsys, &mut sizes);
+    let unused = disk_size - get_size(fsys, &mut sizes);
     sizes.sort();
-    sizes.into_iter().find(|size| unused + size >= 30_000_000).unwrap()
+    sizes.into_iter().find(|size| unused + size >= update_size).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(Day07::part1(&input), 95437);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&input, DISK_SIZE, UPDATE_SIZE), 24933642);
+    }
 }
@@ -1,123 +1,88 @@
-use crate::utility::utils::parse_file;
+use crate::utility::grid::Grid;
+use crate::utility::parse_error::ParseError;
+use super::Solution;
 
-fn read_input() -> Vec<Vec<u32>> {
-    parse_file("inputs/day08.txt", |line| {
-        line.chars().map(|c| c.to_digit(10).unwrap()).collect()
-    })
-}
-
-pub fn solve() {
-    let input = read_input();
+pub struct Day08;
 
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
-
-    println!("Day 08");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+pub fn read_input(contents: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse(contents, "a tree height (0-9)", |c| c.to_digit(10))
 }
 
-fn solve_part1(trees: &Vec<Vec<u32>>) -> usize {
-    let dim = trees.len();
-    let mut vis = vec![vec![0; dim]; dim];
-    let mut biggest_tree;
-    
-    for y in 1..dim-1 {
-        biggest_tree = trees[y][0];
-        for x in 1..dim-1 {
-            let tree = trees[y][x];
-            if tree > biggest_tree {
-                biggest_tree = tree;
-                vis[y][x] += 1;
-            }
-        }
+impl Solution for Day08 {
+    type Input = Grid<u32>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-        biggest_tree = trees[y][dim - 1];
-        for x in (1..dim-1).rev() {
-            let tree = trees[y][x];
-            if tree > biggest_tree {
-                biggest_tree = tree;
-                vis[y][x] += 1;
-            }
-        }
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
     }
 
-    for x in 1..dim-1 {
-        biggest_tree = trees[0][x];
-        for y in 1..dim-1 {
-            let tree = trees[y][x];
-            if tree > biggest_tree {
-                biggest_tree = tree;
-                vis[y][x] += 1;
-            }
-        }
+    fn part1(trees: &Self::Input) -> usize {
+        solve_part1(trees)
+    }
 
-        biggest_tree = trees[dim - 1][x];
-        for y in (1..dim-1).rev() {
-            let tree = trees[y][x];
-            if tree > biggest_tree {
-                biggest_tree = tree;
-                vis[y][x] += 1;
-            }
-        }
+    fn part2(trees: &Self::Input) -> usize {
+        solve_part2(trees)
     }
-    
-    vis.iter().map(|row| row.into_iter().filter(|c| **c > 0).count()).sum::<usize>() + 2 * dim + 2 * (dim - 2)
 }
 
-fn get_view_dist(trees: &Vec<Vec<u32>>, x_start: usize, y_start: usize) -> usize {
-    let dim = trees.len();
-    let height = trees[y_start][x_start];
-    let mut score = 1;
-    
-    let mut dist = 0;
-    for x in x_start+1..dim {
-        dist = x - x_start;
-        if trees[y_start][x] >= height {
-            break;
-        }
-    }
-    score *= dist;
-    
-    dist = 0;
-    for x in (0..x_start).rev() {
-        dist = x_start - x;
-        if trees[y_start][x] >= height {
-            break;
-        }
-    }
-    score *= dist;
+// The trees seen looking left, right, up and down from (x, y), nearest first
+fn lines_of_sight(trees: &Grid<u32>, (x, y): (usize, usize)) -> [Vec<u32>; 4] {
+    let row = trees.row(y);
+    let column: Vec<u32> = trees.column(x).copied().collect();
+    [
+        row[..x].iter().rev().copied().collect(),
+        row[x + 1..].to_vec(),
+        column[..y].iter().rev().copied().collect(),
+        column[y + 1..].to_vec()
+    ]
+}
 
-    dist = 0;
-    for y in y_start+1..dim {
-        dist = y - y_start;
-        if trees[y][x_start] >= height {
-            break;
-        }
-    }
-    score *= dist;
+pub fn solve_part1(trees: &Grid<u32>) -> usize {
+    trees.iter()
+        .filter(|(pos, height)| {
+            lines_of_sight(trees, *pos).iter().any(|line| line.iter().all(|tree| tree < height))
+        })
+        .count()
+}
 
-    dist = 0;
-    for y in (0..y_start).rev() {
-        dist = y_start - y;
-        if trees[y][x_start] >= height {
-            break;
-        }
+fn get_view_dist(line: &[u32], height: u32) -> usize {
+    match line.iter().position(|tree| *tree >= height) {
+        Some(blocked) => blocked + 1,
+        None => line.len()
     }
-    score *= dist;
-    
-    score
 }
 
-fn solve_part2(trees: &Vec<Vec<u32>>) -> usize {
-    let dim = trees.len();
-    let mut scores = vec![vec![1; dim]; dim];
+pub fn solve_part2(trees: &Grid<u32>) -> usize {
+    trees.iter()
+        .map(|(pos, height)| {
+            lines_of_sight(trees, pos).iter().map(|line| get_view_dist(line, *height)).product()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+30373
+25512
+65332
+33549
+35390
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day08::parse(EXAMPLE).unwrap();
+        assert_eq!(Day08::part1(&input), 21);
+    }
 
-    for y in 1..dim-1 {
-        for x in 1..dim-1 {
-            scores[y][x] = get_view_dist(&trees, x, y);
-        }
+    #[test]
+    fn part2_example() {
+        let input = Day08::parse(EXAMPLE).unwrap();
+        assert_eq!(Day08::part2(&input), 8);
     }
-    
-    *scores.iter().map(|row| row.iter().max().unwrap()).max().unwrap()
 }
@@ -1,101 +1,84 @@
-use std::borrow::BorrowMut;
 use std::collections::HashSet;
-use crate::utility::utils::parse_file;
+use crate::utility::parse_error::ParseError;
+use crate::utility::point::{Direction, Point};
+use crate::utility::utils::{parse_lines, parse_number};
+use super::Solution;
+
+pub struct Day09;
 
 #[derive(Debug)]
-enum Move {
-    UP(u32),
-    DOWN(u32),
-    LEFT(u32),
-    RIGHT(u32)
+pub struct Move {
+    direction: Direction,
+    distance: u32
 }
 
 #[derive(Debug)]
 struct Rope {
-    head: (i32, i32),
-    knots: Vec<(i32, i32)>
+    head: Point,
+    knots: Vec<Point>
 }
 
-fn read_input() -> Vec<Move> {
-    parse_file("inputs/day09.txt", |line| {
-        let (c, n) = line.split_at(2);
-        let distance: u32 = n.parse().unwrap();
-        match c {
-            "U " => Move::UP(distance),
-            "D " => Move::DOWN(distance),
-            "L " => Move::LEFT(distance),
-            "R " => Move::RIGHT(distance),
-            _ => panic!("Unknown move: {}", c)
-        }
+pub fn read_input(contents: &str) -> Result<Vec<Move>, ParseError> {
+    parse_lines(contents, |line| {
+        let (c, n) = line.split_once(' ')
+            .ok_or_else(|| ParseError::at(line, line, "a direction and a distance"))?;
+        Ok(Move {
+            direction: c.parse().map_err(|_| ParseError::at(line, c, "U, D, L or R"))?,
+            distance: parse_number(line, n)?
+        })
     })
 }
 
-pub fn solve() {
-    let input = read_input();
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
+impl Solution for Day09 {
+    type Input = Vec<Move>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    println!("Day 09");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-}
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
 
-fn move_knot(leading_knot: &(i32, i32), moving_knot: &(i32, i32)) -> (i32, i32) {
-    let dx = leading_knot.0 - moving_knot.0;
-    let dy = leading_knot.1 - moving_knot.1;
+    fn part1(moves: &Self::Input) -> usize {
+        solve_part1(moves)
+    }
 
-    let tail_dx = dx - dx.signum() * (dx.abs() >= dy.abs()) as i32;
-    let tail_dy = dy - dy.signum() * (dy.abs() >= dx.abs()) as i32;
+    fn part2(moves: &Self::Input) -> usize {
+        solve_part2(moves)
+    }
+}
 
-    (moving_knot.0 + tail_dx, moving_knot.1 + tail_dy)
+// A knot stays put while it touches the one ahead of it, otherwise it takes one step towards it
+fn move_knot(leading_knot: Point, moving_knot: Point) -> Point {
+    let diff = leading_knot - moving_knot;
+    if diff.x.abs() <= 1 && diff.y.abs() <= 1 {
+        moving_knot
+    } else {
+        moving_knot + diff.signum()
+    }
 }
 
-fn nudge_rope(rope: &mut Rope, x_dir: i32, y_dir: i32, visited: &mut HashSet<(i32, i32)>) {
-    rope.head = (rope.head.0 + x_dir, rope.head.1 + y_dir);
+fn nudge_rope(rope: &mut Rope, direction: Direction, visited: &mut HashSet<Point>) {
+    rope.head += direction.step();
 
     let mut leading_knot = rope.head;
-    for i in 0..rope.knots.len() {
-        let current_knot = rope.knots[i].borrow_mut();
-        let new_knot_pos = move_knot(&leading_knot, current_knot);
-
-        current_knot.0 = new_knot_pos.0;
-        current_knot.1 = new_knot_pos.1;
-        
-        leading_knot = new_knot_pos;
+    for knot in rope.knots.iter_mut() {
+        *knot = move_knot(leading_knot, *knot);
+        leading_knot = *knot;
     }
 
-    visited.insert(rope.knots.last().unwrap().clone());
+    visited.insert(*rope.knots.last().unwrap());
 }
 
-fn move_rope(rope: &mut Rope, mov: &Move, visited: &mut HashSet<(i32, i32)>) {
-    match mov {
-        Move::UP(n) => {
-            for _ in 0..*n {
-                nudge_rope(rope, 0, 1, visited);
-            }
-        }
-        Move::DOWN(n) => {
-            for _ in 0..*n {
-                nudge_rope(rope, 0, -1, visited);
-            }
-        }
-        Move::LEFT(n) => {
-            for _ in 0..*n {
-                nudge_rope(rope, -1, 0, visited);
-            }
-        }
-        Move::RIGHT(n) => {
-            for _ in 0..*n {
-                nudge_rope(rope, 1, 0, visited);
-            }
-        }
+fn move_rope(rope: &mut Rope, mov: &Move, visited: &mut HashSet<Point>) {
+    for _ in 0..mov.distance {
+        nudge_rope(rope, mov.direction, visited);
     }
 }
 
-fn solve_part1(moves: &Vec<Move>) -> usize {
-    let mut rope = Rope {head: (0, 0), knots: vec![(0, 0); 1]};
+pub fn solve_part1(moves: &[Move]) -> usize {
+    let mut rope = Rope {head: Point::ORIGIN, knots: vec![Point::ORIGIN; 1]};
     let mut visited = HashSet::new();
-    visited.insert((0, 0));
+    visited.insert(Point::ORIGIN);
 
     for mov in moves {
         move_rope(&mut rope, mov, &mut visited);
@@ -104,10 +87,10 @@ fn solve_part1(moves: &Vec<Move>) -> usize {
     visited.len()
 }
 
-fn solve_part2(moves: &Vec<Move>) -> usize {
-    let mut rope = Rope {head: (0, 0), knots: vec![(0, 0); 9]};
+pub fn solve_part2(moves: &[Move]) -> usize {
+    let mut rope = Rope {head: Point::ORIGIN, knots: vec![Point::ORIGIN; 9]};
     let mut visited = HashSet::new();
-    visited.insert((0, 0));
+    visited.insert(Point::ORIGIN);
 
     for mov in moves {
         move_rope(&mut rope, mov, &mut visited);
@@ -115,3 +98,37 @@ fn solve_part2(moves: &Vec<Move>) -> usize {
 
     visited.len()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day09::parse(EXAMPLE).unwrap();
+        assert_eq!(Day09::part1(&input), 13);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day09::parse(EXAMPLE).unwrap();
+        assert_eq!(Day09::part2(&input), 1);
+    }
+
+    #[test]
+    fn part2_larger_example() {
+        let input = Day09::parse("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n").unwrap();
+        assert_eq!(Day09::part2(&input), 36);
+    }
+}
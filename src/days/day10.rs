@@ -1,9 +1,16 @@
-use crate::utility::utils::parse_file;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{parse_lines, parse_number};
+use super::Solution;
+
+pub struct Day10;
+
+// Cycles it takes to draw the whole screen, which is as far as either part gets
+const CYCLES: usize = 240;
 
 #[derive(Debug)]
-enum Instruction {
-    NOOP,
-    ADDX(i32)
+pub enum Instruction {
+    Noop,
+    Addx(i32)
 }
 
 #[derive(Debug)]
@@ -12,57 +19,66 @@ struct State {
     reg_x: i32
 }
 
-fn read_input() -> Vec<Instruction> {
-    parse_file("inputs/day10.txt", |line| {
-        let split = line.split_once(" ");
+pub fn read_input(contents: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_lines(contents, |line| {
+        let split = line.split_once(' ');
         match split {
             None => {
                 match line {
-                    "noop" => Instruction::NOOP,
-                    _ => panic!("Unknown instruction: {}", line)
+                    "noop" => Ok(Instruction::Noop),
+                    _ => Err(ParseError::at(line, line, "noop or addx <n>"))
                 }
             }
             Some((int, arg)) => {
-                let n: i32 = arg.parse().unwrap();
                 match int {
-                    "addx" => Instruction::ADDX(n),
-                    _ => panic!("Unknown instruction: {}", line)
+                    "addx" => Ok(Instruction::Addx(parse_number(line, arg)?)),
+                    _ => Err(ParseError::at(line, int, "addx"))
                 }
             }
         }
     })
 }
 
-fn add_noop_cycles(input: &Vec<Instruction>) -> Vec<Instruction> {
+pub fn add_noop_cycles(input: &[Instruction]) -> Vec<Instruction> {
     input.iter().flat_map(|int| {
         match int {
-            Instruction::NOOP => vec![Instruction::NOOP],
-            Instruction::ADDX(n) => vec![Instruction::NOOP, Instruction::ADDX(*n)]
+            Instruction::Noop => vec![Instruction::Noop],
+            Instruction::Addx(n) => vec![Instruction::Noop, Instruction::Addx(*n)]
         }
     }).collect()
 }
 
-pub fn solve() {
-    let mut input = read_input();
-    input = add_noop_cycles(&input);
-    let part1 = solve_part1(&input);
+impl Solution for Day10 {
+    type Input = Vec<Instruction>;
+    type Part1 = i32;
+    type Part2 = Vec<String>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        let instructions = add_noop_cycles(&read_input(contents)?);
+        if instructions.len() < CYCLES {
+            return Err(ParseError::end_of_input(contents, format!("a program running for at least {} cycles", CYCLES)));
+        }
+        Ok(instructions)
+    }
+
+    fn part1(instructions: &Self::Input) -> i32 {
+        solve_part1(instructions)
+    }
 
-    println!("Day 10");
-    println!("Part 1: {}", part1);
-    println!("Part 2:");
-    solve_part2(&input);
+    fn part2(instructions: &Self::Input) -> Vec<String> {
+        solve_part2(instructions)
+    }
 }
 
-fn simulate(state: &mut State, instructions: &Vec<Instruction>, simulate: usize) -> i32 {
-    for ip in state.ip..state.ip+simulate {
-        let int = &instructions[ip];
+fn simulate(state: &mut State, instructions: &[Instruction], simulate: usize) -> i32 {
+    for int in &instructions[state.ip..state.ip+simulate] {
         match int {
-            Instruction::NOOP => {}
-            Instruction::ADDX(n) => state.reg_x += *n
+            Instruction::Noop => {}
+            Instruction::Addx(n) => state.reg_x += *n
         }
     }
     
-    state.ip = state.ip + simulate;
+    state.ip += simulate;
     get_signal_strength(state)
 }
 
@@ -70,33 +86,223 @@ fn get_signal_strength(state: &State) -> i32 {
     state.reg_x * ( state.ip as i32 + 1 )
 }
 
-fn solve_part1(instructions: &Vec<Instruction>) -> i32 {
+pub fn solve_part1(instructions: &[Instruction]) -> i32 {
     let mut state = State { ip: 0, reg_x: 1 };
     let mut sum = 0;
     
-    sum += simulate(&mut state, &instructions, 19);
-    sum += simulate(&mut state, &instructions, 40);
-    sum += simulate(&mut state, &instructions, 40);
-    sum += simulate(&mut state, &instructions, 40);
-    sum += simulate(&mut state, &instructions, 40);
-    sum += simulate(&mut state, &instructions, 40);
+    sum += simulate(&mut state, instructions, 19);
+    sum += simulate(&mut state, instructions, 40);
+    sum += simulate(&mut state, instructions, 40);
+    sum += simulate(&mut state, instructions, 40);
+    sum += simulate(&mut state, instructions, 40);
+    sum += simulate(&mut state, instructions, 40);
     
     
     sum
 }
 
-fn solve_part2(instructions: &Vec<Instruction>) {
+pub fn solve_part2(instructions: &[Instruction]) -> Vec<String> {
     let mut state = State { ip: 0, reg_x: 1 };
+    let mut screen = vec![];
 
     for _ in 0..6 {
+        let mut row = String::new();
         for x in 0..40 {
             if x == state.reg_x - 1 || x == state.reg_x  || x == state.reg_x + 1  {
-                print!("⚪");
+                row.push('⚪');
             } else {
-                print!("⚫");
+                row.push('⚫');
             }
-            simulate(&mut state, &instructions, 1);
+            simulate(&mut state, instructions, 1);
+        }
+        screen.push(row);
+    }
+
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+    const SCREEN: [&str; 6] = [
+        "##..##..##..##..##..##..##..##..##..##..",
+        "###...###...###...###...###...###...###.",
+        "####....####....####....####....####....",
+        "#####.....#####.....#####.....#####.....",
+        "######......######......######......####",
+        "#######.......#######.......#######....."
+    ];
+
+    #[test]
+    fn part1_example() {
+        let input = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(Day10::part1(&input), 13140);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day10::parse(EXAMPLE).unwrap();
+        let expected: Vec<String> = SCREEN.iter()
+            .map(|row| row.replace('#', "⚪").replace('.', "⚫"))
+            .collect();
+        assert_eq!(Day10::part2(&input), expected);
+    }
+
+    #[test]
+    fn program_too_short_for_the_screen() {
+        for contents in ["", "noop\naddx 3\n"] {
+            let error = Day10::parse(contents).unwrap_err();
+            assert_eq!(error.expected, "a program running for at least 240 cycles");
         }
-        println!();
     }
 }
@@ -1,15 +1,19 @@
-use std::fs::read_to_string;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{blocks, parse_blocks, parse_number};
+use super::Solution;
+
+pub struct Day11;
 
 #[derive(Debug, Clone)]
 enum Operand {
-    OLD,
-    LITERAL(u64)
+    Old,
+    Literal(u64)
 }
 
 #[derive(Debug, Clone)]
 enum Operator {
-    PLUS,
-    TIMES
+    Plus,
+    Times
 }
 
 #[derive(Debug, Clone)]
@@ -20,7 +24,7 @@ struct Operation {
 }
 
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     id: usize,
     items: Vec<u64>,
     operation: Operation,
@@ -36,9 +40,9 @@ impl Monkey {
             id: 0,
             items: vec![],
             operation: Operation {
-                left: Operand::OLD,
-                right: Operand::OLD,
-                operator: Operator::PLUS
+                left: Operand::Old,
+                right: Operand::Old,
+                operator: Operator::Plus
             },
             test: 0,
             when_true: 0,
@@ -48,90 +52,115 @@ impl Monkey {
     }
 }
 
-fn read_input() -> Vec<Monkey> {
-    let mut m = Monkey::new();
+pub fn read_input(contents: &str) -> Result<Vec<Monkey>, ParseError> {
+    // Both parts multiply the two highest inspection counts
+    let count = blocks(contents).len();
+    if count < 2 {
+        return Err(ParseError::end_of_input(contents, "at least 2 monkeys"));
+    }
+    parse_blocks(contents, |block| parse_monkey(block, count))
+}
 
-    let contents = match read_to_string("inputs/day11.txt") {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    let lines = contents.split("\r\n");
-    
-    let mut result: Vec<Monkey> = vec![];
-    for line in lines {
-        if line.starts_with("Monkey") {
-            m = Monkey::new();
-            let (_, id) = line.split_at(7);
-            m.id = id[..1].parse().unwrap();
-        } else if line.contains("Starting items") {
-            let (_, items_raw) = line.split_at(18);
-            m.items = items_raw.split(", ").into_iter().map(|i| i.parse().unwrap()).collect();
-        } else if line.contains("Operation") {
-            let (_, op_raw) = line.split_at(19);
-            m.operation = parse_op(op_raw);
-        } else if line.contains("Test") {
-            let (_, div) = line.split_at(21);
-            m.test = div.parse().unwrap();
-        } else if line.contains("If true") {
-            let (_, id) = line.split_at(29);
-            m.when_true = id.parse().unwrap();
-        } else if line.contains("If false") {
-            let (_, id) = line.split_at(30);
-            m.when_false = id.parse().unwrap();
-            result.push(m.clone());
+// Line `i` of the block must start with `prefix` (after indentation); returns the line and what follows the prefix.
+fn field<'a>(block: &[&'a str], i: usize, prefix: &str) -> Result<(&'a str, &'a str), ParseError> {
+    match block.get(i) {
+        None => Err(ParseError::end_of_input(&block.join("\n"), prefix)),
+        Some(line) => line.trim_start().strip_prefix(prefix)
+            .map(|rest| (*line, rest.trim_start()))
+            .ok_or_else(|| ParseError::at(line, line.trim_start(), prefix).offset(i))
+    }
+}
+
+// `count` is the number of monkeys, which every throw has to land on
+fn parse_monkey(block: &[&str], count: usize) -> Result<Monkey, ParseError> {
+    let target = |line: &str, id: &str, i: usize| -> Result<usize, ParseError> {
+        let target = parse_number(line, id).map_err(|error| error.offset(i))?;
+        match target < count {
+            true => Ok(target),
+            false => Err(ParseError::at(line, id, format!("a monkey from 0 to {}", count - 1)).offset(i))
         }
+    };
+
+    let mut m = Monkey::new();
+
+    let (line, id) = field(block, 0, "Monkey")?;
+    m.id = parse_number(line, id.strip_suffix(':').unwrap_or(id))?;
+
+    let (line, items_raw) = field(block, 1, "Starting items:")?;
+    m.items = items_raw.split(", ")
+        .map(|i| parse_number(line, i).map_err(|error| error.offset(1)))
+        .collect::<Result<_, _>>()?;
+
+    let (line, op_raw) = field(block, 2, "Operation: new =")?;
+    m.operation = parse_op(line, op_raw).map_err(|error| error.offset(2))?;
+
+    let (line, div) = field(block, 3, "Test: divisible by")?;
+    m.test = parse_number(line, div).map_err(|error| error.offset(3))?;
+    if m.test == 0 {
+        return Err(ParseError::at(line, div, "a divisor above 0").offset(3));
     }
+
+    let (line, id) = field(block, 4, "If true: throw to monkey")?;
+    m.when_true = target(line, id, 4)?;
+
+    let (line, id) = field(block, 5, "If false: throw to monkey")?;
+    m.when_false = target(line, id, 5)?;
     
-    result
+    Ok(m)
 }
 
-fn parse_op(text: &str) -> Operation {
-    let op = if text.contains("*") { "*" } else { "+" };
-    let ops: Vec<Operand> = text.split(op).into_iter().map(|o: &str| 
-        match o.trim().parse() {
-           Err(_) => Operand::OLD,
-            Ok(n) => Operand::LITERAL(n)
-        }
-    ).collect();
+fn parse_op(line: &str, text: &str) -> Result<Operation, ParseError> {
+    let (left, operator, right) = if let Some((left, right)) = text.split_once(" * ") {
+        (left, Operator::Times, right)
+    } else if let Some((left, right)) = text.split_once(" + ") {
+        (left, Operator::Plus, right)
+    } else {
+        return Err(ParseError::at(line, text, "<operand> * <operand> or <operand> + <operand>"));
+    };
+
+    let operand = |o: &str| match o {
+        "old" => Ok(Operand::Old),
+        n => n.parse().map(Operand::Literal).map_err(|_| ParseError::at(line, o, "old or a number"))
+    };
     
-    Operation {
-        left: ops[0].clone(),
-        right: ops[1].clone(),
-        operator: if op == "+" { Operator::PLUS } else { Operator::TIMES }
-    }
+    Ok(Operation {
+        left: operand(left)?,
+        right: operand(right)?,
+        operator
+    })
 }
 
 fn apply_m(worry: &u64, op: &Operation, m: u64) -> u64 {
     let left = match &op.left {
-        Operand::OLD => worry,
-        Operand::LITERAL(n) => n
+        Operand::Old => worry,
+        Operand::Literal(n) => n
     };
     let right = match &op.right {
-        Operand::OLD => worry,
-        Operand::LITERAL(n) => n
+        Operand::Old => worry,
+        Operand::Literal(n) => n
     };
     match op.operator {
-        Operator::PLUS => (left + right) % m,
-        Operator::TIMES => (left * right) % m
+        Operator::Plus => (left + right) % m,
+        Operator::Times => (left * right) % m
     }
 }
 
 fn apply_s(worry: &u64, op: &Operation) -> u64 {
     let left = match &op.left {
-        Operand::OLD => worry,
-        Operand::LITERAL(n) => n
+        Operand::Old => worry,
+        Operand::Literal(n) => n
     };
     let right = match &op.right {
-        Operand::OLD => worry,
-        Operand::LITERAL(n) => n
+        Operand::Old => worry,
+        Operand::Literal(n) => n
     };
     match op.operator {
-        Operator::PLUS => (left + right) / 3,
-        Operator::TIMES => (left * right) / 3
+        Operator::Plus => (left + right) / 3,
+        Operator::Times => (left * right) / 3
     }
 }
 
-fn monkey_around<F>(id: usize, monkeys: &mut Vec<Monkey>, apply: F)
+fn monkey_around<F>(id: usize, monkeys: &mut [Monkey], apply: F)
     where F: Fn(&u64, &Operation) -> u64 {
     let mut transfers: Vec<(usize, u64)> = vec![];
     {
@@ -141,7 +170,7 @@ fn monkey_around<F>(id: usize, monkeys: &mut Vec<Monkey>, apply: F)
             monkey.total_inspections += 1;
             let worry = apply(item, &monkey.operation);
             
-            if worry % monkey.test == 0 {
+            if worry.is_multiple_of(monkey.test) {
                 transfers.push((monkey.when_true, worry));
             } else {
                 transfers.push((monkey.when_false, worry));
@@ -156,17 +185,25 @@ fn monkey_around<F>(id: usize, monkeys: &mut Vec<Monkey>, apply: F)
     }
 }
 
-pub fn solve() {
-    let mut input = read_input();
-    let part1 = solve_part1(&mut input.clone());
-    let part2 = solve_part2(&mut input);
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
+
+    fn part1(monkeys: &Self::Input) -> u64 {
+        solve_part1(&mut monkeys.clone())
+    }
 
-    println!("Day 11");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    fn part2(monkeys: &Self::Input) -> u64 {
+        solve_part2(&mut monkeys.clone())
+    }
 }
 
-fn simulate<F>(rounds: u32, monkeys: &mut Vec<Monkey>, apply: F) -> u64
+fn simulate<F>(rounds: u32, monkeys: &mut [Monkey], apply: F) -> u64
     where F: Fn(&u64, &Operation) -> u64 {
     for _ in 0..rounds {
         for id in 0..monkeys.len() {
@@ -180,11 +217,70 @@ fn simulate<F>(rounds: u32, monkeys: &mut Vec<Monkey>, apply: F) -> u64
     
 }
 
-fn solve_part1(monkeys: &mut Vec<Monkey>) -> u64 {
+pub fn solve_part1(monkeys: &mut [Monkey]) -> u64 {
     simulate(20, monkeys, apply_s)
 }
 
-fn solve_part2(monkeys: &mut Vec<Monkey>) -> u64 {
+pub fn solve_part2(monkeys: &mut [Monkey]) -> u64 {
     let m = monkeys.iter().map(|m| m.test).product();
     simulate(10000, monkeys, |worry, op| apply_m(worry, op, m))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(Day11::part1(&input), 10605);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day11::parse(EXAMPLE).unwrap();
+        assert_eq!(Day11::part2(&input), 2713310158);
+    }
+
+    #[test]
+    fn throws_must_land_on_a_monkey() {
+        assert_eq!(Day11::parse("").unwrap_err().expected, "at least 2 monkeys");
+
+        let contents = EXAMPLE.replace("If false: throw to monkey 1", "If false: throw to monkey 4");
+        let error = Day11::parse(&contents).unwrap_err();
+        assert_eq!((error.line, error.expected.as_str(), error.found.as_str()), (28, "a monkey from 0 to 3", "4"));
+
+        let contents = EXAMPLE.replace("divisible by 23", "divisible by 0");
+        assert_eq!(Day11::parse(&contents).unwrap_err().expected, "a divisor above 0");
+    }
+}
@@ -1,455 +1,414 @@
-use std::cmp::min;
-use std::collections::{HashMap, HashSet};
-use std::fs::read_to_string;
-use std::thread::current;
-use std::time::SystemTime;
-
-struct GridGraph {
-    width: i32,
-    height: i32,
-    grid: Vec<i32>
-}
+use crate::settings::Settings;
+use crate::utility::grid::Grid;
+use crate::utility::parse_error::ParseError;
+use crate::utility::pathfinding::{self, Path};
+use crate::utility::point::{Direction, Point};
+use super::Solution;
 
-impl GridGraph {
-    fn new(    width: i32,
-               height: i32, grid: Vec<i32>) -> Self {
-        GridGraph { width, height, grid }
-    }
-    
-    fn get_neighbours(self: &Self, center: (i32, i32)) -> Vec<i32> {
-        let mut result = vec![];
-        for (x, y) in [(center.0 +1, center.1 ), (center.0 -1,center.1 ), (center.0, center.1 +1), (center.0 ,center.1 -1)] {
-            if x >= 0 && x < self.width && y >= 0 && y < self.height {
-                result.push(self.grid[(y * self.width + x) as usize]);
-            }
-        }
-        
-        result
-    }
-}
+pub struct Day12;
 
 #[derive(Debug, Clone)]
-struct Input {
-    elevations: Vec<Vec<u32>>,
-    start: (usize, usize),
-    end: (usize, usize)
+pub struct Input {
+    elevations: Grid<u32>,
+    starts: Vec<(usize, usize)>,
+    ends: Vec<(usize, usize)>
 }
 
-#[derive(Debug, Clone)]
-struct Graph {
-    width: usize,
-    height: usize,
-    adjacency: Vec<Vec<Option<i32>>>
-}
+pub fn read_input(contents: &str) -> Result<Input, ParseError> {
+    let map = Grid::parse(contents, "a lowercase letter, S or E", |c| match c {
+        'a'..='z' | 'S' | 'E' => Some(c),
+        _ => None
+    })?;
 
-#[derive(Debug, Clone)]
-struct GraphSearch {
-    graph: Graph,
-    working: Vec<(usize, usize)>,
-    visited: HashSet<(usize, usize)>,
-    costs: HashMap<(usize, usize), u32>
+    let markers = |marker: char| -> Vec<(usize, usize)> {
+        map.iter().filter(|(_, c)| **c == marker).map(|(pos, _)| pos).collect()
+    };
+    let starts = markers('S');
+    let ends = markers('E');
+    if starts.is_empty() {
+        return Err(ParseError::end_of_input(contents, "a start marker S"));
+    }
+    if ends.is_empty() {
+        return Err(ParseError::end_of_input(contents, "a summit marker E"));
+    }
+
+    Ok(Input {
+        elevations: map.map(|c| match c {
+            'S' => 0,
+            'E' => 25,
+            c => *c as u32 - 97
+        }),
+        starts,
+        ends
+    })
 }
 
-#[derive(Debug, Clone)]
-struct BellmanFord {
-    graph: Graph,
-    distance: HashMap<(usize, usize), i32>,
-    pred: HashMap<(usize, usize), (usize, usize)>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Movement {
+    /// Up, down, left and right.
+    FourWay,
+    /// Diagonally as well.
+    EightWay
 }
 
-#[derive(Debug, Clone)]
-struct AStar {
-    graph: Graph,
-    start_node: (usize, usize),
-    cost: HashMap<(usize, usize), i32>,
-    h_cost: HashMap<(usize, usize), i32>,
-    pred: HashMap<(usize, usize), (usize, usize)>,
-    open: HashSet<(usize, usize)>
+/// Which steps between neighbouring squares are allowed and what they cost.
+#[derive(Debug, Clone, Copy)]
+pub struct ClimbingRules {
+    /// Highest climb allowed in one step.
+    pub max_ascent: u32,
+    /// Deepest drop allowed in one step, None for no limit.
+    pub max_descent: Option<u32>,
+    pub movement: Movement,
+    /// Cost of a step given the change in elevation, positive going up.
+    pub cost: fn(i32) -> u64
 }
 
-impl AStar {
-    fn new(graph: Graph, start_node: (usize, usize)) -> Self {
-        AStar {
-            graph,
-            start_node,
-            cost: HashMap::new(),
-            h_cost: HashMap::new(),
-            pred: HashMap::new(),
-            open: HashSet::new()
+impl ClimbingRules {
+    /// The rules from the puzzle: at most one higher, any way down, every step costs 1.
+    pub const PUZZLE: ClimbingRules = ClimbingRules {
+        max_ascent: 1,
+        max_descent: None,
+        movement: Movement::FourWay,
+        cost: |_| 1
+    };
+
+    pub fn allows(&self, diff: i32) -> bool {
+        if diff >= 0 {
+            diff.unsigned_abs() <= self.max_ascent
+        } else {
+            self.max_descent.is_none_or(|max| diff.unsigned_abs() <= max)
         }
     }
-    
-    fn initialize(self: &mut Self, start_node: (usize, usize)) {
-        self.cost.clear();
-        self.h_cost.clear();
-        self.pred.clear();
-        self.open.clear();
-        self.start_node = start_node;
-        for y in 0..self.graph.height {
-            for x in 0..self.graph.width {
-                self.cost.insert((x, y), (self.graph.height * self.graph.width + 99999) as i32);
-                self.h_cost.insert((x, y), (self.graph.height * self.graph.width + 99999) as i32);
-            }
+
+    /// Cost of a step from elevation `from` to `to`, None if it isn't allowed.
+    pub fn step_cost(&self, from: u32, to: u32) -> Option<u64> {
+        let diff = to as i32 - from as i32;
+        if self.allows(diff) { Some((self.cost)(diff)) } else { None }
+    }
+
+    fn neighbours(&self, elevations: &Grid<u32>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        match self.movement {
+            Movement::FourWay => elevations.neighbours4(pos).collect(),
+            Movement::EightWay => elevations.neighbours8(pos).collect()
         }
-        self.cost.insert(start_node, 0);
-        self.h_cost.insert(start_node, AStar::heuristic(&start_node, &start_node));
-        self.open.insert(start_node);
     }
-    
-    fn get_edge_cost(self: &mut Self, from: &(usize, usize), to: &(usize, usize)) -> i32 {
-        self.graph.adjacency[self.graph.width * from.1 + from.0][self.graph.width * to.1 + to.0].unwrap()
+
+    // Fewest steps between two squares if elevation didn't matter
+    fn steps_between(&self, from: (usize, usize), to: (usize, usize)) -> u64 {
+        let offset = Point::from(to) - Point::from(from);
+        let steps = match self.movement {
+            Movement::FourWay => offset.x.abs() + offset.y.abs(),
+            Movement::EightWay => offset.x.abs().max(offset.y.abs())
+        };
+        steps as u64
     }
-    
-    fn heuristic(start_node: &(usize, usize), node: &(usize, usize)) -> i32 {
-        (start_node.0 as i32 - node.0 as i32).abs() + (start_node.1 as i32 - node.1 as i32).abs()
+
+    // The cheapest step there can be, so that steps times this never overestimates the cost of a route
+    fn min_step_cost(&self) -> u64 {
+        (-25..=25).filter(|diff| self.allows(*diff)).map(self.cost).min().unwrap_or(0)
     }
+}
 
-    fn find(self: &mut Self, target_node: (usize, usize)) -> Result<i32, String> {
-        while !self.open.is_empty() {
-            //println!("Open set size {}", self.open.len());
-            let current: (usize, usize) = *self.open.iter()
-                .min_by(|a, b| self.h_cost[*a].cmp(&self.h_cost[*b]))
-                .unwrap();
-            //println!("Self.open {:?} candiate = {}", current, self.h_cost[&current]);
+// Squares next to `pos` that can be climbed to, with the cost of the step
+fn climbable(elevations: &Grid<u32>, rules: &ClimbingRules, pos: (usize, usize)) -> Vec<((usize, usize), u64)> {
+    rules.neighbours(elevations, pos).into_iter()
+        .filter_map(|nb| rules.step_cost(elevations[pos], elevations[nb]).map(|cost| (nb, cost)))
+        .collect()
+}
 
-            if current == target_node {
-                return Ok(self.get_steps(target_node));
-            }
-            
-            self.open.remove(&current);
-            
-            let nbs = self.get_neighbours(current);
-            for nb in nbs {
-                let score = self.cost[&current] + self.get_edge_cost(&current, &nb);
-                if score < self.cost[&nb] {
-                    self.pred.insert(nb, current);
-                    self.cost.insert(nb, score);
-                    self.h_cost.insert(nb, score + AStar::heuristic(&self.start_node, &nb));
-                    if !self.open.contains(&nb) {
-                        self.open.insert(nb);
-                    }
-                }
-            }
-        }
+// The reverse of `climbable`: squares next to `pos` that could be climbed from to get to `pos`
+fn descendable(elevations: &Grid<u32>, rules: &ClimbingRules, pos: (usize, usize)) -> Vec<((usize, usize), u64)> {
+    rules.neighbours(elevations, pos).into_iter()
+        .filter_map(|nb| rules.step_cost(elevations[nb], elevations[pos]).map(|cost| (nb, cost)))
+        .collect()
+}
 
-        return Err("No Path".to_string());
+/// Cost of the cheapest route from every square to a summit, found by searching backwards from the
+/// E markers; None where no summit can be reached.
+pub fn distances_to_summit(input: &Input, rules: &ClimbingRules) -> Grid<Option<u64>> {
+    let elevations = &input.elevations;
+    let costs = pathfinding::dijkstra_costs(input.ends.clone(), |pos| descendable(elevations, rules, *pos));
+    let mut result = Grid::new(elevations.width(), elevations.height(), None);
+    for (pos, cost) in costs {
+        result[pos] = Some(cost);
     }
+    result
+}
+
+type Pos = (usize, usize);
 
-    fn get_neighbours(self: &mut Self, node: (usize, usize)) -> Vec<(usize, usize)> {
-        let adj = &self.graph.adjacency;
-        let row: &Vec<Option<i32>> = &adj[self.graph.width * node.1 + node.0];
-        let mut nbs = vec![];
-
-        for y in 0..self.graph.height {
-            for x in 0..self.graph.width {
-                match row[y * self.graph.width + x] {
-                    None => {},
-                    Some(_) => nbs.push((x, y))
-                }
+/// The searches day 12 can be solved with. They all find the cheapest route, except `Bfs`, which
+/// finds the one with the fewest steps and so only agrees with the others when every step costs
+/// the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    AStar,
+    Bfs,
+    BellmanFord,
+    Dijkstra
+}
+
+algorithms!(Algorithm { AStar => "astar", Bfs => "bfs", BellmanFord => "bellman-ford", Dijkstra => "dijkstra" });
+
+impl Algorithm {
+    // Cheapest route from one of `starts` to a square `is_goal` accepts; `moves` gives the squares one
+    // step away with the cost of getting there. `heuristic` is only used by A*.
+    fn search<FM, FH, FG>(self, elevations: &Grid<u32>, starts: &[Pos], mut moves: FM, heuristic: FH, is_goal: FG)
+        -> Option<Path<Pos>>
+    where FM: FnMut(&Pos) -> Vec<(Pos, u64)>,
+          FH: FnMut(&Pos) -> u64,
+          FG: FnMut(&Pos) -> bool {
+        let starts = starts.iter().copied();
+        match self {
+            Algorithm::AStar => pathfinding::astar(starts, moves, heuristic, is_goal),
+            Algorithm::Dijkstra => pathfinding::dijkstra(starts, moves, is_goal),
+            Algorithm::BellmanFord => {
+                let squares: Vec<Pos> = elevations.positions().collect();
+                pathfinding::bellman_ford(&squares, starts, moves, is_goal)
+            },
+            Algorithm::Bfs => {
+                let mut path = pathfinding::bfs(starts, |pos| moves(pos).into_iter().map(|(nb, _)| nb), is_goal)?;
+                // What the fewest steps cost
+                path.cost = path.nodes.windows(2)
+                    .map(|step| moves(&step[0]).into_iter().find(|(nb, _)| *nb == step[1]).unwrap().1)
+                    .sum();
+                Some(path)
             }
         }
-        
-        nbs
     }
-    
-    fn get_steps(self: &mut Self, target_node: (usize, usize)) -> i32 {
-        let mut current = target_node;
-        let mut steps = 0;
-        //println!("Pred {:?}", self.pred);
-        while current != self.start_node {
-            //println!("Current {:?}", current);
-            steps += 1;
-            current = self.pred[&current];
-        }
+}
 
-        steps
-    }
+// Cheapest route from any S to any E
+fn climb(input: &Input, rules: &ClimbingRules, algorithm: Algorithm) -> Option<Path<Pos>> {
+    let min_step_cost = rules.min_step_cost();
+    algorithm.search(
+        &input.elevations,
+        &input.starts,
+        |pos| climbable(&input.elevations, rules, *pos),
+        |pos| input.ends.iter().map(|end| rules.steps_between(*pos, *end)).min().unwrap_or(0) * min_step_cost,
+        |pos| input.ends.contains(pos)
+    )
 }
-/*
-impl BellmanFord {
-    fn new(graph: Graph, start_node: (usize, usize)) -> Self {
-        let mut distance = HashMap::new();
-        for y in 0..graph.height {
-            for x in 0..graph.width {
-                distance.insert((x, y), 999999);
-            }
-        }
-        distance.insert(start_node, 0);
-        
-        BellmanFord {
-            graph,
-            distance,
-            pred: HashMap::new()
-        }
+
+// Cheapest route to any E from a square at elevation a, searching back from the summits. There is no
+// useful estimate of the way to the nearest a, so A* is no better than Dijkstra here.
+fn descend(input: &Input, rules: &ClimbingRules, algorithm: Algorithm) -> Option<Path<Pos>> {
+    let elevations = &input.elevations;
+    let mut path = algorithm.search(
+        elevations,
+        &input.ends,
+        |pos| descendable(elevations, rules, *pos),
+        |_| 0,
+        |pos| elevations[*pos] == 0
+    )?;
+    path.nodes.reverse();
+    Some(path)
+}
+
+impl Solution for Day12 {
+    type Input = Input;
+    type Part1 = Option<u64>;
+    type Part2 = Option<u64>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
     }
-    
-    fn relax(self: &mut Self) {
-        for from_i in 0..self.graph.adjacency.len() {
-            let from = (from_i % self.graph.width, from_i / self.graph.width);
-            for to_i in 0..self.graph.adjacency.len() {
-                if !self.graph.adjacency[from_i][to_i] { // No edge
-                    continue;
-                }
-
-                
-
-                // y * width + x
-                let to = (to_i % self.graph.width, to_i / self.graph.width);
-                //println!("Doing edge {:?}->{:?}", from, to);
-                //println!("from {:?} to {:?}, gw {}, gh {}", from, to, self.graph.width, self.graph.height);
-                if self.distance[&from] + 1 < self.distance[&to] {
-                    self.distance.insert(to, self.distance[&from] + 1);
-                    self.pred.insert(to, from);
-                }                
-            }
-        }
+
+    fn part1(input: &Self::Input) -> Option<u64> {
+        solve_part1(input, &ClimbingRules::PUZZLE, Algorithm::AStar) // 497
     }
-    
-    fn find(self: &mut Self, start_node: (usize, usize), target_node: (usize, usize)) -> i32 {
-        println!("Called find");
-        let relaxations = (self.graph.width * self.graph.height) - 1;
-        let pc1 = relaxations / 100;
-        let elapsed = SystemTime::now();
-        for step in 0..relaxations {
-            //if step % pc1 == 0 {
-                println!("{}/{} done", step, relaxations);
-            //}
-            self.relax();
-
-            println!("{}/{} done", step, relaxations);
-            let steps_remaining = (relaxations - step - 1) as f64;
-            let time_per_step: f64 = elapsed.elapsed().unwrap().as_millis() as f64 / (step + 1) as f64;
-            println!("Estimated time remaining: {} minutes", steps_remaining * time_per_step / 1000f64 / 60f64 / 60f64)
-            
-        }
-        
-        let mut current = target_node;
-        let mut steps = 0;
-        //println!("Pred {:?}", self.pred);
-        while current != start_node {
-            //println!("Current {:?}", current);
-            steps += 1;
-            current = self.pred[&current];
-        }
-        
-        steps
+
+    fn part2(input: &Self::Input) -> Option<u64> {
+        solve_part2(input, &ClimbingRules::PUZZLE, Algorithm::AStar) // 492
     }
-}
 
-impl GraphSearch {
-    fn new(graph: Graph, start_node: (usize, usize)) -> Self {
-        let mut costs = HashMap::new();
-        let mut visited = HashSet::new();
-        costs.insert(start_node, 0);
-        visited.insert(start_node);
-        let mut gs = GraphSearch {
-            graph,
-            working: vec![],
-            visited,
-            costs
-        };
+    const ALGORITHMS: &'static [&'static str] = &Algorithm::NAMES;
 
-        gs.visit_neighbours(start_node);
-        
-        gs
+    fn part1_with(input: &Self::Input, settings: &Settings) -> Option<u64> {
+        solve_part1(input, &ClimbingRules::PUZZLE, Algorithm::from_settings(settings).unwrap())
     }
-    
-    fn find_step(self: &mut GraphSearch, target_node: (usize, usize)) -> Option<&u32> {
-        if self.visited.contains(&target_node) {
-            return self.costs.get(&target_node)
-        }
 
-        let current_node = self.working.pop().unwrap();
-        self.visit_neighbours(current_node);        
-        
-        None
+    fn part2_with(input: &Self::Input, settings: &Settings) -> Option<u64> {
+        solve_part2(input, &ClimbingRules::PUZZLE, Algorithm::from_settings(settings).unwrap())
     }
-    
-    fn visit_neighbours(self: &mut GraphSearch, current_node: (usize, usize)) {
-        println!("visit_neighbours ( {:?} )", current_node);
-        let neighbours = self.get_neighbours(current_node);
-
-        for nb in neighbours {
-            if !self.visited.contains(&nb) {
-                self.visited.insert(nb);
-                self.add_neighbours(nb);
+}
+
+/// A cheapest route from an S to an E, both included.
+pub fn route_part1(input: &Input, rules: &ClimbingRules, algorithm: Algorithm) -> Option<Vec<(usize, usize)>> {
+    climb(input, rules, algorithm).map(|path| path.nodes)
+}
+
+/// A cheapest route to an E from any square at elevation a, both included.
+pub fn route_part2(input: &Input, rules: &ClimbingRules, algorithm: Algorithm) -> Option<Vec<(usize, usize)>> {
+    descend(input, rules, algorithm).map(|path| path.nodes)
+}
+
+/// The map in the style of the puzzle text: each square on `route` shows the way to the next one
+/// with `^`, `v`, `<` or `>` (or a diagonal arrow), the summits are `E` and everything else is `.`.
+pub fn render_route(input: &Input, route: &[(usize, usize)]) -> String {
+    let mut map = input.elevations.map(|_| '.');
+    for step in route.windows(2) {
+        let offset = Point::from(step[1]) - Point::from(step[0]);
+        map[step[0]] = match Direction::from_step(offset) {
+            Some(Direction::Up) => '^',
+            Some(Direction::Down) => 'v',
+            Some(Direction::Left) => '<',
+            Some(Direction::Right) => '>',
+            None => match (offset.x, offset.y) {
+                (1, -1) => '↗',
+                (1, 1) => '↘',
+                (-1, 1) => '↙',
+                (-1, -1) => '↖',
+                _ => panic!("{:?} and {:?} are not next to each other", step[0], step[1])
             }
-            
-            let new_cost = self.costs.get(&current_node).unwrap() + 1;
-            self.update_cost(nb, new_cost);
-        }
+        };
     }
-    
-    fn update_cost(self: &mut GraphSearch, node: (usize, usize), new_cost: u32) {
-        println!("update_cost ( {:?}, {} )", node, new_cost);
-        match self.costs.get(&node) {
-            None => {
-                self.costs.insert(node, new_cost);
-            }
-            Some(&current_cost) => {
-                self.costs.insert(node, min(current_cost, new_cost));
-            }
-        }
+    for end in &input.ends {
+        map[*end] = 'E';
     }
-    
-    fn add_neighbours(self: &mut GraphSearch, node: (usize, usize)) {
-        let neighbours = self.get_neighbours(node);
-        
-        for nb in neighbours {
-            if !self.visited.contains(&nb) {
-                self.working.push(nb);
-            }
-        }
+    map.to_string()
+}
+
+/// Cost of the cheapest route from an S to an E, if the rules allow one.
+pub fn solve_part1(input: &Input, rules: &ClimbingRules, algorithm: Algorithm) -> Option<u64> {
+    climb(input, rules, algorithm).map(|path| path.cost)
+}
+
+/// Cost of the cheapest route to an E from any square at elevation a, if the rules allow one.
+pub fn solve_part2(input: &Input, rules: &ClimbingRules, algorithm: Algorithm) -> Option<u64> {
+    descend(input, rules, algorithm).map(|path| path.cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::part1(&input), Some(31));
     }
-    
-    fn get_neighbours(self: &mut GraphSearch, current_node: (usize, usize)) -> Vec<(usize, usize)> {
-        let adj = &self.graph.adjacency;
-        let row: &Vec<bool> = &adj[self.graph.width * current_node.1 + current_node.0];
-        let mut nbs = vec![];
-        
-        for y in 0..self.graph.height {
-            for x in 0..self.graph.width {
-                if row[y * self.graph.width + x] {
-                    nbs.push((x, y));
-                }
-            }
-        }
 
-        println!("get_neighbours( {:?} ) = {:?}", current_node, nbs);
-        nbs
+    #[test]
+    fn part2_example() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::part2(&input), Some(29));
     }
-}
-*/
-fn read_input() -> Input {
-    let contents = match read_to_string("inputs/day12.txt") {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    let lines = contents.split("\r\n");
-    
-    let mut y = 0;
-    let mut x = 0;
-    let mut start = (0, 0);
-    let mut end= (0, 0);
-    
-    let mut evs = vec![];
-    for line in lines {
-        if line.is_empty() {
-            continue;
-        }
-        x = 0;
-        let row: Vec<u32> = line.chars().map(|c| {
-            let ev = match c {
-                'S' => {
-                    start = (x, y);
-                    0
-                },
-                'E' => {
-                    end = (x, y);
-                    25
-                },
-                k => k as u32 - 97
+
+    // Every step of the drawing follows an arrow to a square that is at most one higher
+    fn check_rendered_route(input: &Input, route: &[(usize, usize)], start: (usize, usize)) {
+        let rendered = render_route(input, route);
+        let map: Vec<Vec<char>> = rendered.lines().map(|line| line.chars().collect()).collect();
+
+        let mut pos = start;
+        for _ in 1..route.len() {
+            let next = match map[pos.1][pos.0] {
+                '^' => (pos.0, pos.1 - 1),
+                'v' => (pos.0, pos.1 + 1),
+                '<' => (pos.0 - 1, pos.1),
+                '>' => (pos.0 + 1, pos.1),
+                c => panic!("route broken at {:?}: {}", pos, c)
             };
-            x += 1;
-            ev
-        }).collect();
-        evs.push(row);
-        y += 1;
+            assert!(input.elevations[next] <= input.elevations[pos] + 1);
+            pos = next;
+        }
+        assert_eq!(pos, input.ends[0]);
+        assert_eq!(map[pos.1][pos.0], 'E');
     }
-    
-    Input {
-        elevations: evs,
-        start,
-        end
+
+    #[test]
+    fn routes_are_drawn_from_start_to_summit() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        let rules = ClimbingRules::PUZZLE;
+
+        let route = route_part1(&input, &rules, Algorithm::AStar).unwrap();
+        assert_eq!((route.len(), route[0]), (32, input.starts[0]));
+        check_rendered_route(&input, &route, input.starts[0]);
+
+        let route = route_part2(&input, &rules, Algorithm::AStar).unwrap();
+        assert_eq!(route.len(), 30);
+        assert_eq!(input.elevations[route[0]], 0);
+        check_rendered_route(&input, &route, route[0]);
     }
-}
 
-fn create_adjacency_matrix(elevations: &Vec<Vec<u32>>) -> Graph {
-    let height = elevations.len();
-    let width = elevations[0].len();
-    let mut result = vec![];
-    for y in 0..height {
-        for x in 0..width {
-            let mut row = vec![];
-            for ny in 0..height {
-                for nx in 0..width {
-                    let t_dist = (x as i32 - nx as i32).abs() + (y as i32 - ny as i32).abs();
-                    row.push(if t_dist == 1 {
-                        let diff = elevations[ny][nx] as i32 - elevations[y][x] as i32;
-                        if diff > 1 { None } else {Some(1)}
-                        /*if diff > 1 { None } else { 
-                            Some(match diff {
-                                1 => 1,
-                                0 => 1,
-                                d => -d + 2
-                            })
-                        }*/
-                    } else { None });
-                }
-            }
-            result.push(row);
+    #[test]
+    fn distances_to_summit_example() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        let distances = distances_to_summit(&input, &ClimbingRules::PUZZLE);
+        assert_eq!(distances[input.ends[0]], Some(0));
+        assert_eq!(distances[input.starts[0]], Some(31));
+        assert_eq!(distances.iter().filter_map(|(_, distance)| *distance).max(), Some(31));
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let input = Day12::parse(EXAMPLE).unwrap();
+        for name in Day12::ALGORITHMS {
+            let settings = Settings { algorithm: Some(name.to_string()), ..Settings::default() };
+            assert_eq!((Day12::part1_with(&input, &settings), Day12::part2_with(&input, &settings)), (Some(31), Some(29)), "{}", name);
+        }
+
+        // Bfs ignores step costs, the others don't
+        let input = read_input("SzaE\naaaa").unwrap();
+        let rules = ClimbingRules { cost: |diff| if diff < 0 { (2 - diff) as u64 } else { 1 }, ..ANY_CLIMB };
+        for algorithm in Algorithm::ALL {
+            let expected = if algorithm == Algorithm::Bfs { 29 } else { 5 };
+            assert_eq!(solve_part1(&input, &rules, algorithm), Some(expected), "{}", algorithm.name());
         }
-        
+        assert!("bellman".parse::<Algorithm>().is_err());
+        let settings = Settings { algorithm: Some("bellman".to_string()), ..Settings::default() };
+        assert!(Algorithm::from_settings(&settings).is_err());
+        assert_eq!(Algorithm::from_settings(&Settings::default()), Ok(Algorithm::AStar));
     }
-    
-    Graph {
-        width, height, adjacency: result
+
+    const ANY_CLIMB: ClimbingRules = ClimbingRules { max_ascent: 25, ..ClimbingRules::PUZZLE };
+
+    #[test]
+    fn several_markers() {
+        let input = read_input("SaaaaaS\naaaaaaa\naaaaaaE").unwrap();
+        assert_eq!((input.starts.len(), input.ends.len()), (2, 1));
+        assert_eq!(solve_part1(&input, &ANY_CLIMB, Algorithm::AStar), Some(2));
+        assert_eq!(route_part1(&input, &ANY_CLIMB, Algorithm::AStar).unwrap()[0], (6, 0));
+        assert!(read_input("aaaE").is_err());
     }
-}
 
-pub fn solve() {
-    let mut input = read_input();
-    println!("Input read.");
-    let graph = create_adjacency_matrix(&input.elevations);
-    println!("Graph created");
-    let part1 = solve_part1(&input, graph.clone());
-    let part2 = solve_part2(&input, graph.clone());
-
-    println!("Day 11");
-    println!("Part 1: {}", part1); // 497
-    println!("Part 2: {}", part2); // 492
-}
+    #[test]
+    fn diagonal_movement() {
+        let input = read_input("Saaa\naaaa\naaaE").unwrap();
+        assert_eq!(solve_part1(&input, &ANY_CLIMB, Algorithm::AStar), Some(5));
+        let rules = ClimbingRules { movement: Movement::EightWay, ..ANY_CLIMB };
+        assert_eq!(solve_part1(&input, &rules, Algorithm::AStar), Some(3));
+        assert!(render_route(&input, &route_part1(&input, &rules, Algorithm::AStar).unwrap()).contains('↘'));
+    }
 
-fn solve_part1(input: &Input, graph: Graph) -> i32 {
-    let mut gs = AStar::new(graph, input.start);
-    gs.initialize(input.start);
-    println!("A* initialized");
-    
-    gs.find(input.end).unwrap()
-}
+    #[test]
+    fn descent_limit_and_step_costs() {
+        // Over the z is shorter, but means a drop of 25
+        let input = read_input("SzaE\naaaa").unwrap();
+        assert_eq!(solve_part1(&input, &ANY_CLIMB, Algorithm::AStar), Some(3));
+        assert_eq!(solve_part1(&input, &ClimbingRules { max_descent: Some(0), ..ANY_CLIMB }, Algorithm::AStar), Some(5));
 
-fn solve_part2(input: &Input, graph: Graph) -> i32 {
-    let mut candidates = HashSet::new();
-    for y in 0..graph.height {
-        for x in 0..graph.width {
-            if input.elevations[y][x] == 0 {
-                let mut any_b = false;
-                for (nx, ny) in [(x as i32 +1, y as i32), (x as i32-1,y as i32), (x as i32, y as i32+1), (x as i32,y as i32-1)] {
-                    if (nx >= 0 && nx < graph.width as i32 && ny >= 0 && ny < graph.height as i32 && input.elevations[ny as usize][nx as usize] == 1) {
-                        any_b = true;
-                        break;
-                    }
-                }
-                if any_b {
-                    candidates.insert((x, y));
-                }
-            }
-        }
+        // Going down costs more the steeper it is
+        let rules = ClimbingRules { cost: |diff| if diff < 0 { (2 - diff) as u64 } else { 1 }, ..ANY_CLIMB };
+        assert_eq!(solve_part1(&input, &rules, Algorithm::AStar), Some(5));
+        assert_eq!(distances_to_summit(&input, &rules)[(1, 0)], Some(27 + 1));
     }
-    
-    println!("Candiates {:?}", candidates);
-    let mut gs = AStar::new(graph, input.start);
-    
-    let mut result = vec![];
-    for c in candidates {
-        gs.initialize(c);
-        let length = gs.find(input.end);
-        if length.is_ok() {
-            result.push(length.unwrap());
+
+    #[test]
+    fn no_route() {
+        let input = Day12::parse("SzE").unwrap();
+        assert_eq!((Day12::part1(&input), Day12::part2(&input)), (None, None));
+        for algorithm in Algorithm::ALL {
+            assert_eq!(solve_part1(&input, &ClimbingRules::PUZZLE, algorithm), None, "{}", algorithm.name());
+            assert_eq!(solve_part2(&input, &ClimbingRules::PUZZLE, algorithm), None, "{}", algorithm.name());
         }
     }
-    
-    println!("{:?}", result);
-    
-    *result.iter().min().unwrap()
 }
@@ -1,59 +1,90 @@
 use std::cell::RefCell;
 use std::cmp::{min, Ordering};
 use std::rc::Rc;
-use crate::utility::utils::parse_file;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::parse_lines;
+use super::Solution;
+
+pub struct Day13;
 
 #[derive(Debug, Clone)]
-enum Tree {
+pub enum Tree {
     Node(Rc<RefCell<Vec<Tree>>>),
     Leaf(i32)
 }
 
-fn read_input() -> Vec<Tree> {
-    parse_file("inputs/day13.txt", |line| {
-        let parsed = parse_tree(&mut line.chars().collect());
-        let unparsed = unparse_tree(&parsed);
-        
-        if line != unparsed {
-            panic!("Fucked up {} !== {}", line, unparsed);
-        }
-        
-        parsed
-    })
+// Packets come in pairs, and there has to be at least one
+pub fn read_input(contents: &str) -> Result<Vec<Tree>, ParseError> {
+    let packets = parse_lines(contents, parse_packet)?;
+    match packets.len() {
+        0 => Err(ParseError::end_of_input(contents, "a packet")),
+        n if n % 2 == 1 => Err(ParseError::end_of_input(contents, "the second packet of the pair")),
+        _ => Ok(packets)
+    }
 }
 
-fn parse_tree(tokens: &mut Vec<char>) -> Tree {
-    let c = tokens.remove(0);
-    if c == '[' {
-        let mut contents: Vec<Tree> = vec![];
-        let mut peek = tokens[0];
-        while peek != ']' {
-            let next = parse_tree(tokens);
-            contents.push(next);
-            if tokens[0] == ',' {
-                tokens.remove(0);
+pub fn parse_packet(line: &str) -> Result<Tree, ParseError> {
+    let width = line.chars().count();
+    let mut tokens = line.chars().collect();
+    let parsed = parse_tree(&mut tokens, width)?;
+    if !tokens.is_empty() {
+        return Err(ParseError::new(width - tokens.len() + 1, "end of line", tokens.iter().collect::<String>()));
+    }
+
+    let unparsed = unparse_tree(&parsed);
+    if line != unparsed {
+        return Err(ParseError::at(line, line, unparsed));
+    }
+
+    Ok(parsed)
+}
+
+// `width` is the length of the whole line, so the column of the next token is `width - tokens.len() + 1`
+fn parse_tree(tokens: &mut Vec<char>, width: usize) -> Result<Tree, ParseError> {
+    let unexpected = |tokens: &Vec<char>, expected: &str| {
+        let found = tokens.first().map_or("end of line".to_string(), |c| c.to_string());
+        ParseError::new(width - tokens.len() + 1, expected, found)
+    };
+
+    match tokens.first() {
+        Some('[') => {
+            tokens.remove(0);
+            let mut contents: Vec<Tree> = vec![];
+            while tokens.first() != Some(&']') {
+                let next = parse_tree(tokens, width)?;
+                contents.push(next);
+                match tokens.first() {
+                    Some(',') => { tokens.remove(0); },
+                    Some(']') => {},
+                    _ => return Err(unexpected(tokens, ", or ]"))
+                }
             }
-            peek = tokens[0];
-        }
-        tokens.remove(0);
-        
-        Tree::Node(Rc::new(RefCell::new(contents)))
-    } else {
-        let mut num_str = c.to_string();
-        while tokens[0] != ',' && tokens[0] != ']' {
-            num_str.push_str(&tokens.remove(0).to_string());
-        }
-        Tree::Leaf(num_str.parse().unwrap())
+            tokens.remove(0);
+
+            Ok(Tree::Node(Rc::new(RefCell::new(contents))))
+        },
+        Some(c) if c.is_ascii_digit() => {
+            let column = width - tokens.len() + 1;
+            let mut num_str = String::new();
+            while tokens.first().is_some_and(|c| c.is_ascii_digit()) {
+                num_str.push(tokens.remove(0));
+            }
+            match num_str.parse() {
+                Ok(n) => Ok(Tree::Leaf(n)),
+                Err(_) => Err(ParseError::new(column, "a number", num_str))
+            }
+        },
+        _ => Err(unexpected(tokens, "[ or a number"))
     }
 }
 
 fn unparse_tree(tree: &Tree) -> String {
     match tree {
         Tree::Node(children) => {
-            let c: Vec<String> = children.borrow().iter().map(|t| unparse_tree(t)).collect();
+            let c: Vec<String> = children.borrow().iter().map(unparse_tree).collect();
             let mut result = "[".to_string();
             result.push_str(&c.join(","));
-            result.push_str("]");
+            result.push(']');
             result
         },
         Tree::Leaf(n) => n.to_string()
@@ -70,18 +101,18 @@ impl Eq for Tree {}
 
 impl PartialOrd for Tree {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(match compare(self, other) {
-            -1 => Ordering::Less,
-            0 => Ordering::Equal,
-            1 => Ordering::Greater,
-            n => panic!("Comparison result incorrect: {}", n)
-        })
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Tree {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        match compare(self, other) {
+            -1 => Ordering::Less,
+            0 => Ordering::Equal,
+            1 => Ordering::Greater,
+            n => panic!("Comparison result incorrect: {}", n)
+        }
     }
 }
 
@@ -103,7 +134,7 @@ fn compare(tree_a: &Tree, tree_b: &Tree) -> i32 {
 
                     (ba.len() as i32 - bb.len() as i32).signum()
                 }
-                Tree::Leaf(b) => {
+                Tree::Leaf(_) => {
                     if ba.is_empty() {
                         -1
                     } else {
@@ -138,20 +169,25 @@ fn compare(tree_a: &Tree, tree_b: &Tree) -> i32 {
     }
 }
 
-pub fn solve() {
-    let mut input = read_input();
-    
-    //println!("{:?}", input);
-    
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
-    
-    println!("Day 13");
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+impl Solution for Day13 {
+    type Input = Vec<Tree>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        read_input(contents)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &Vec<Tree>) -> usize {
+pub fn solve_part1(input: &[Tree]) -> usize {
     let mut sum = 0;
     for i in (0..input.len() - 1).step_by(2) {
         // println!("Comparing pair {}: {} to {}", i / 2 + 1, i, i+ 1);
@@ -166,10 +202,10 @@ fn solve_part1(input: &Vec<Tree>) -> usize {
     sum
 }
 
-fn solve_part2(input: &Vec<Tree>) -> usize {
-    let mut list = (*input).clone();
-    let div_1 = parse_tree(&mut "[[2]]".chars().collect());
-    let div_2 = parse_tree(&mut "[[6]]".chars().collect());
+pub fn solve_part2(input: &[Tree]) -> usize {
+    let mut list = input.to_vec();
+    let div_1 = parse_packet("[[2]]").unwrap();
+    let div_2 = parse_packet("[[6]]").unwrap();
     list.push(div_1.clone());
     list.push(div_2.clone());
 
@@ -177,3 +213,53 @@ fn solve_part2(input: &Vec<Tree>) -> usize {
 
     (1 + list.binary_search(&div_1).unwrap()) * (1 + list.binary_search(&div_2).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day13::parse(EXAMPLE).unwrap();
+        assert_eq!(Day13::part1(&input), 13);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day13::parse(EXAMPLE).unwrap();
+        assert_eq!(Day13::part2(&input), 140);
+    }
+
+    #[test]
+    fn packets_come_in_pairs() {
+        assert_eq!(Day13::parse("").unwrap_err().expected, "a packet");
+        let error = Day13::parse("[1]\n[2]\n\n[3]\n").unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (4, "the second packet of the pair"));
+    }
+}
@@ -1,181 +1,371 @@
-use std::cmp::{max, min};
-use std::fmt::{ Display, Formatter};
-use std::time::SystemTime;
-use crate::utility::utils::parse_file;
-
-fn read_input() -> Vec<Vec<(usize, usize)>> {
-    parse_file("inputs/day14.txt", |line| {
-        line.split("->").into_iter().map(|pair| {
-            let (from, to) = pair.trim().split_once(",").unwrap();
-            (from.parse().unwrap(), to.parse().unwrap())
-        }).collect()
+use std::fmt::{Display, Formatter};
+use crate::utility::grid::Grid;
+use crate::utility::parse_error::ParseError;
+use crate::utility::point::Point;
+use crate::utility::utils::{parse_lines, parse_number};
+use super::Solution;
+
+pub struct Day14;
+
+pub fn read_input(contents: &str) -> Result<Vec<Vec<Point>>, ParseError> {
+    parse_lines(contents, |line| {
+        let mut path: Vec<Point> = vec![];
+        for pair in line.split("->").map(|pair| pair.trim()) {
+            let (from, to) = pair.split_once(',')
+                .ok_or_else(|| ParseError::at(line, pair, "a point like 498,4"))?;
+            let point = Point::new(parse_number(line, from)?, parse_number(line, to)?);
+            if let Some(last) = path.last() {
+                if last.x != point.x && last.y != point.y {
+                    return Err(ParseError::at(line, pair, "a point in line with the previous one"));
+                }
+            }
+            path.push(point);
+        }
+        Ok(path)
     })
 }
 
 #[derive(Clone, PartialEq)]
 enum Tile {
-    AIR, ROCK, SAND
+    Air, Rock, Sand
+}
+
+impl Display for Tile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Tile::Air => ".",
+            Tile::Rock => "█",
+            Tile::Sand => "S"
+        })
+    }
+}
+
+// Straight down first, then diagonally left, then diagonally right
+const FALLS: [Point; 3] = [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)];
+
+/// Where a grain of sand ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grain {
+    Rested(Point),
+    /// Fell past the lowest rock, with where the floor caught it if there is one.
+    Abyss(Option<Point>),
+    /// Every source is covered in sand, so no more can come out.
+    Blocked
+}
+
+/// Sets up a `Sandbox`: the puzzle's is `SandboxBuilder::new(rocks).floor(2).build()`.
+#[derive(Debug, Clone)]
+pub struct SandboxBuilder {
+    rocks: Vec<Vec<Point>>,
+    sources: Vec<Point>,
+    floor: Option<i64>
+}
+
+impl SandboxBuilder {
+    /// The puzzle's source at 500,0 and no floor, until set otherwise.
+    pub const SOURCE: Point = Point::new(500, 0);
+
+    pub fn new(rocks: &[Vec<Point>]) -> Self {
+        SandboxBuilder { rocks: rocks.to_vec(), sources: vec![], floor: None }
+    }
+
+    /// Adds a source of sand; sand comes from each source in turn, in the order they were added.
+    pub fn source(mut self, source: Point) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// A floor of endless width, `depth` rows below the lowest rock or source, so at least one row
+    /// below everything.
+    ///
+    /// # Panics
+    ///
+    /// If `depth` is less than 1.
+    pub fn floor(mut self, depth: i64) -> Self {
+        assert!(depth >= 1, "Floor depth must be at least 1, not {}", depth);
+        self.floor = Some(depth);
+        self
+    }
+
+    pub fn no_floor(mut self) -> Self {
+        self.floor = None;
+        self
+    }
+
+    pub fn build(self) -> Sandbox {
+        let sources = if self.sources.is_empty() { vec![SandboxBuilder::SOURCE] } else { self.sources };
+        let points = || self.rocks.iter().flatten().chain(sources.iter());
+        let lowest_rock = points().map(|point| point.y).max().unwrap();
+        let floor = self.floor.map(|depth| lowest_rock + depth);
+
+        // Sand spreads at most one column sideways per row it falls, so with a floor it stays inside a
+        // triangle below each source. Without one, a grain that gets past the rocks falls straight down.
+        let spread = |source: &Point| floor.map_or(1, |floor| floor - source.y + 1);
+        let left = points().map(|point| point.x).chain(sources.iter().map(|source| source.x - spread(source))).min().unwrap() - 1;
+        let right = points().map(|point| point.x).chain(sources.iter().map(|source| source.x + spread(source))).max().unwrap() + 1;
+        let top = points().map(|point| point.y).min().unwrap();
+        let bottom = floor.unwrap_or(lowest_rock + 1);
+
+        let mut sandbox = Sandbox {
+            tiles: Grid::new((right - left + 1) as usize, (bottom - top + 1) as usize, Tile::Air),
+            offset: Point::new(left, top),
+            trails: sources.iter().map(|_| vec![]).collect(),
+            next_source: 0,
+            sources,
+            lowest_rock,
+            floor
+        };
+        for line in &self.rocks {
+            if let [point] = line.as_slice() {
+                sandbox.draw_line(*point, *point);
+            }
+            for pair in line.windows(2) {
+                sandbox.draw_line(pair[0], pair[1]);
+            }
+        }
+        if let Some(floor) = floor {
+            sandbox.draw_line(Point::new(left, floor), Point::new(right, floor));
+        }
+        sandbox
+    }
 }
 
+/// Points are in puzzle coordinates; `tiles` starts at `offset`.
 #[derive(Clone)]
-struct Sandbox {
-    tiles: Vec<Tile>,
-    width: usize,
-    height: usize,
-    drop_x: usize,
-    fast_pos: Option<(usize, usize)>
+pub struct Sandbox {
+    tiles: Grid<Tile>,
+    offset: Point,
+    sources: Vec<Point>,
+    // The way the last grain from each source fell, so the next one can start where it is still the same
+    trails: Vec<Vec<Point>>,
+    next_source: usize,
+    lowest_rock: i64,
+    floor: Option<i64>
 }
 
 impl Sandbox {
-    fn new(max_y: usize) -> Self {
-        let height = max_y + 3;
-        let width = height * 2 - 1 + 2;
-        let offset = 500 - width / 2;
-        Sandbox {
-            tiles: vec![Tile::AIR; width * height],
-            width,
-            height,
-            drop_x: 500 - offset,
-            fast_pos: None
-        }
+    fn width(&self) -> usize {
+        self.tiles.width()
+    }
+
+    fn height(&self) -> usize {
+        self.tiles.height()
+    }
+
+    /// The lowest rock (or source, if that is lower); sand that gets below it is in the abyss, even if
+    /// the floor catches it.
+    pub fn lowest_rock(&self) -> i64 {
+        self.lowest_rock
     }
-    
-    fn put(self: &mut Self, point: (usize, usize), tile: Tile) {
-        self.tiles[point.1 * self.width + point.0] = tile;
+
+    fn put(&mut self, point: Point, tile: Tile) {
+        let pos = (point - self.offset).to_usize().expect("point left of or above the sandbox");
+        self.tiles[pos] = tile;
+    }
+
+    fn is_air(&self, point: Point) -> bool {
+        self.tiles.at(point - self.offset) == Some(&Tile::Air)
     }
 
-    fn get(self: &Self, point: (usize, usize)) -> &Tile {
-        if point.1 == self.height - 1 {
-            return &Tile::ROCK;
+    fn draw_line(&mut self, from: Point, to: Point) {
+        if from.x != to.x && from.y != to.y {
+            panic!("Diagonal line: {} to {}", from, to);
+        }
+
+        let step = (to - from).signum();
+        let mut point = from;
+        self.put(point, Tile::Rock);
+        while point != to {
+            point += step;
+            self.put(point, Tile::Rock);
         }
-        &self.tiles[point.1 * self.width + point.0]
     }
-    
-    fn draw_line(self: &mut Self, from: (usize, usize), to: (usize, usize)) {
-        if from.0 == to.0 {
-            for y in min(from.1, to.1)..max(from.1, to.1)+1 {
-                self.put((from.0, y), Tile::ROCK);
-            }
-        } else if from.1 == to.1 {
-            for x in min(from.0, to.0)..max(from.0, to.0)+1 {
-                self.put((x, from.1), Tile::ROCK);
+
+    /// Lets one grain of sand fall from the next source that isn't blocked.
+    pub fn drop_sand(&mut self) -> Grain {
+        for i in 0..self.sources.len() {
+            let source = (self.next_source + i) % self.sources.len();
+            let grain = self.drop_from(source);
+            if grain != Grain::Blocked {
+                self.next_source = (source + 1) % self.sources.len();
+                return grain;
             }
-        } else {
-            panic!("Diagonal line: {:?} to {:?}", from, to);
         }
+        Grain::Blocked
     }
 
-    fn place_sand(self: &mut Self) -> (usize, usize) {
-        let mut sand = if self.fast_pos.is_none() {
-            (self.drop_x, 0)
-        } else {
-            self.fast_pos.unwrap()
-        };
-        let mut prev_pos = None;
-        loop {
-            if *self.get((sand.0, sand.1 + 1)) == Tile::AIR {
-                prev_pos = Some(sand);
-                sand.1 += 1;
-            } else if sand.0 > 0 && *self.get((sand.0 - 1, sand.1 + 1)) == Tile::AIR {
-                prev_pos = Some(sand);
-                sand.0 -= 1;
-                sand.1 += 1;
-            } else if sand.0 < self.width - 1 && *self.get((sand.0 + 1, sand.1 + 1)) == Tile::AIR {
-                prev_pos = Some(sand);
-                sand.0 += 1;
-                sand.1 += 1;
-            } else {
-                self.fast_pos = prev_pos;
-                self.put(sand, Tile::SAND);
-                return sand;
+    fn drop_from(&mut self, source: usize) -> Grain {
+        let mut trail = std::mem::take(&mut self.trails[source]);
+        // Sand from elsewhere may have landed on the old trail since
+        if let Some(filled) = trail.iter().position(|point| !self.is_air(*point)) {
+            trail.truncate(filled);
+        }
+        if trail.is_empty() {
+            if !self.is_air(self.sources[source]) {
+                return Grain::Blocked;
             }
+            trail.push(self.sources[source]);
         }
+
+        let grain = loop {
+            let sand = *trail.last().unwrap();
+            if self.floor.is_none() && sand.y > self.lowest_rock {
+                break Grain::Abyss(None);
+            }
+            match FALLS.iter().map(|fall| sand + *fall).find(|next| self.is_air(*next)) {
+                Some(next) => trail.push(next),
+                None => {
+                    self.put(sand, Tile::Sand);
+                    trail.pop();
+                    break if sand.y > self.lowest_rock { Grain::Abyss(Some(sand)) } else { Grain::Rested(sand) };
+                }
+            }
+        };
+        self.trails[source] = trail;
+        grain
     }
 }
 
 impl Display for Sandbox {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/{}\n", self.width, self.height).unwrap();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                write!(f, "{}", match self.get((x, y)) {
-                    Tile::AIR => ".",
-                    Tile::ROCK => "█",
-                    Tile::SAND => "S"
-                }).unwrap();
-            }
-            write!(f, "\n").unwrap();
-        }
-        Ok(())
+        writeln!(f, "{}/{}", self.width(), self.height())?;
+        writeln!(f, "{}", self.tiles)
     }
 }
 
-fn create_sandbox(lines: &Vec<Vec<(usize, usize)>>) -> Sandbox {
-    let mut min_x: usize = usize::MAX;
-    let mut max_x: usize = 0;
-    let mut max_y: usize = 0;
+/// The puzzle's sandbox: sand from 500,0 and a floor two below the lowest rock.
+pub fn create_sandbox(lines: &[Vec<Point>]) -> Sandbox {
+    SandboxBuilder::new(lines).floor(2).build()
+}
 
-    for line in lines {
-        for point in line {
-            min_x = min(min_x, point.0);
-            max_x = max(max_x, point.0);
-            max_y = max(max_y, point.1);
-        }
+impl Solution for Day14 {
+    type Input = Sandbox;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        Ok(create_sandbox(&read_input(contents)?))
     }
 
-    let height = max_y + 3;
-    let width = height * 2 - 1 + 2;
-    let offset = 500 - width / 2;
-    let mut sandbox = Sandbox {
-        tiles: vec![Tile::AIR; width * height],
-        width,
-        height,
-        drop_x: 500 - offset,
-        fast_pos: None
-    };
-
-    for line in lines {
-        for i in 0..line.len() - 1 {
-            let from = (line[i].0 - offset, line[i].1);
-            let to = (line[i+1].0 - offset, line[i+1].1);
-            sandbox.draw_line(from, to);
-        }
+    fn part1(sandbox: &Self::Input) -> usize {
+        solve_part1(&mut sandbox.clone()) // 1072
     }
-    
-    sandbox
-}
 
-pub fn solve() {
-    let elapsed = SystemTime::now();
-    
-    let mut input = read_input();
-    let mut sandbox = create_sandbox(&input);
-
-    
-    let part1 = solve_part1(&mut sandbox.clone());
-    let part2 = solve_part2(&mut sandbox.clone());
-    
-    println!("Elapsed: {}ms", elapsed.elapsed().unwrap().as_millis());
-
-    println!("Day 14");
-    println!("Part 1: {}", part1); // 1072
-    println!("Part 2: {}", part2); // 24659
+    fn part2(sandbox: &Self::Input) -> usize {
+        solve_part2(&mut sandbox.clone()) // 24659
+    }
 }
 
-fn solve_part1(sandbox: &mut Sandbox) -> usize {
+/// Grains that come to rest before the first one ends up in the abyss.
+pub fn solve_part1(sandbox: &mut Sandbox) -> usize {
     let mut i = 0;
-    while sandbox.place_sand().1 <= sandbox.height - 3 {
+    while let Grain::Rested(_) = sandbox.drop_sand() {
         i += 1;
     }
-    
-    i 
+
+    i
 }
 
-fn solve_part2(sandbox: &mut Sandbox) -> usize {
+/// Grains that come to rest before the sources are blocked (or sand falls into an abyss with no floor).
+pub fn solve_part2(sandbox: &mut Sandbox) -> usize {
     let mut i = 0;
-    while sandbox.place_sand() != (sandbox.drop_x, 0) {
+    while let Grain::Rested(_) | Grain::Abyss(Some(_)) = sandbox.drop_sand() {
         i += 1;
     }
 
-    i + 1
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(Day14::part1(&input), 24);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day14::parse(EXAMPLE).unwrap();
+        assert_eq!(Day14::part2(&input), 93);
+    }
+
+    fn shifted(rocks: &[Vec<Point>], by: Point) -> Vec<Vec<Point>> {
+        rocks.iter().map(|line| line.iter().map(|point| *point + by).collect()).collect()
+    }
+
+    #[test]
+    fn abyss_without_floor() {
+        let rocks = read_input(EXAMPLE).unwrap();
+        let mut sandbox = SandboxBuilder::new(&rocks).build();
+        assert_eq!(solve_part1(&mut sandbox), 24);
+        assert_eq!(sandbox.drop_sand(), Grain::Abyss(None));
+    }
+
+    #[test]
+    fn floor_catches_the_abyss() {
+        let mut sandbox = Day14::parse(EXAMPLE).unwrap();
+        // The 25th grain, which part 1 stops at, still rests on the floor
+        assert_eq!(solve_part1(&mut sandbox), 24);
+        // So does the 26th, after rolling off the left end of the lowest rock
+        assert_eq!(sandbox.drop_sand(), Grain::Abyss(Some(Point::new(492, 10))));
+        assert_eq!(solve_part2(&mut sandbox), 93 - 26);
+    }
+
+    #[test]
+    fn no_floor_takes_the_floor_away() {
+        let rocks = read_input(EXAMPLE).unwrap();
+        let mut sandbox = SandboxBuilder::new(&rocks).floor(2).no_floor().build();
+        assert_eq!(solve_part2(&mut sandbox.clone()), 24);
+        assert_eq!(solve_part1(&mut sandbox), 24);
+    }
+
+    #[test]
+    #[should_panic(expected = "Floor depth must be at least 1, not 0")]
+    fn floor_must_be_below_everything() {
+        SandboxBuilder::new(&[]).floor(0);
+    }
+
+    #[test]
+    fn any_coordinates() {
+        let rocks = read_input(EXAMPLE).unwrap();
+        for by in [Point::new(-500, 0), Point::new(-1500, -40), Point::new(10_000, 7)] {
+            let build = || SandboxBuilder::new(&shifted(&rocks, by)).source(SandboxBuilder::SOURCE + by);
+            assert_eq!(solve_part1(&mut build().build()), 24, "shifted by {}", by);
+            assert_eq!(solve_part2(&mut build().floor(2).build()), 93, "shifted by {}", by);
+        }
+    }
+
+    #[test]
+    fn floor_depth() {
+        let rocks = read_input(EXAMPLE).unwrap();
+        // Right under the lowest rock the floor leaves no room for an abyss, so no grain is lost
+        let sandbox = SandboxBuilder::new(&rocks).floor(1).build();
+        let rested = solve_part2(&mut sandbox.clone());
+        assert_eq!(solve_part1(&mut sandbox.clone()), rested);
+        assert!(rested < 93);
+        assert!(solve_part2(&mut SandboxBuilder::new(&rocks).floor(4).build()) > 93);
+        // With nothing in the way the sand heaps up in a triangle, one row for each unit of depth
+        assert_eq!(solve_part2(&mut SandboxBuilder::new(&[]).floor(5).build()), 25);
+    }
+
+    #[test]
+    fn several_sources() {
+        let rocks = read_input(EXAMPLE).unwrap();
+        let mut both = rocks.clone();
+        both.extend(shifted(&rocks, Point::new(100, 0)));
+        let build = || SandboxBuilder::new(&both).source(Point::new(500, 0)).source(Point::new(600, 0));
+
+        // Far enough apart that the two heaps never meet
+        assert_eq!(solve_part2(&mut build().floor(2).build()), 2 * 93);
+        // Sources take turns, so the first grain into the abyss is the 25th from the first source
+        assert_eq!(solve_part1(&mut build().build()), 2 * 24);
+    }
 }
@@ -1,196 +1,594 @@
-use std::cmp::{max, min};
 use std::collections::HashSet;
-use std::fmt::{Debug, Display, Formatter};
-use std::time::SystemTime;
-use regex::{Captures, Match, Regex};
-use crate::utility::utils::parse_file;
+use std::fmt::{Debug, Formatter};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::thread;
+use regex::Regex;
+use crate::settings::Settings;
+use crate::utility::intervals::IntervalSet;
+use crate::utility::parse_error::ParseError;
+use crate::utility::point::Point;
+use crate::utility::utils::{parse_lines, parse_number};
+use super::Solution;
 
-struct SensorReading {
-    sensor: (i32, i32),
-    beacon: (i32, i32)
+pub struct Day15;
+
+/// The numbers the puzzle text gives, which differ between the example and the real input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    /// Row to count excluded cells on in part 1.
+    pub row: i64,
+    /// The distress beacon is somewhere in the square from (0, 0) to (bound, bound).
+    pub bound: i64,
+    /// Tuning frequency is x * multiplier + y.
+    pub multiplier: i64
+}
+
+impl Params {
+    pub const PUZZLE: Params = Params { row: 2_000_000, bound: 4_000_000, multiplier: 4_000_000 };
+    pub const EXAMPLE: Params = Params { row: 10, bound: 20, multiplier: 4_000_000 };
+
+    /// The puzzle's values, with any that `settings` changes.
+    pub fn from_settings(settings: &Settings) -> Params {
+        Params {
+            row: settings.param::<Day15>("row"),
+            bound: settings.param::<Day15>("bound"),
+            multiplier: settings.param::<Day15>("multiplier")
+        }
+    }
+
+    // Anything larger would overflow the rotated coordinates long before a scan got anywhere
+    const MAX_BOUND: i64 = i64::MAX / 4;
+
+    /// Whether the values make sense, i.e. the search square isn't empty or too big to search.
+    pub fn check(&self) -> Result<(), String> {
+        if !(0..=Params::MAX_BOUND).contains(&self.bound) {
+            return Err(format!("Day 15 bound must be from 0 to {}, not {}", Params::MAX_BOUND, self.bound));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct SensorReading {
+    pub sensor: Point,
+    pub beacon: Point
+}
+
+impl SensorReading {
+    /// How far the sensor can see: every cell at least as close as its beacon.
+    pub fn reach(&self) -> i64 {
+        self.sensor.manhattan(self.beacon)
+    }
+
+    pub fn covers(&self, point: Point) -> bool {
+        self.sensor.manhattan(point) <= self.reach()
+    }
+
+    // The cells of row `y` within reach, if any
+    fn row_span(&self, y: i64) -> Option<(i64, i64)> {
+        let r = self.reach() - (self.sensor.y - y).abs();
+        if r < 0 { None } else { Some((self.sensor.x - r, self.sensor.x + r)) }
+    }
+
+    // The cells of column `x` within reach, if any
+    fn column_span(&self, x: i64) -> Option<(i64, i64)> {
+        let r = self.reach() - (self.sensor.x - x).abs();
+        if r < 0 { None } else { Some((self.sensor.y - r, self.sensor.y + r)) }
+    }
 }
 
 impl Debug for SensorReading {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {}) senses ({}, {})", self.sensor.0, self.sensor.1, self.beacon.0, self.beacon.1)
+        write!(f, "{} senses {}", self.sensor, self.beacon)
     }
 }
 
-fn read_input() -> Vec<SensorReading> {
+pub fn read_input(contents: &str) -> Result<Vec<SensorReading>, ParseError> {
     let sensor_rgx = Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)").unwrap();
-    parse_file("inputs/day15.txt", |line| {
-        let c = sensor_rgx.captures(line).unwrap();
+    parse_lines(contents, |line| {
+        let c = sensor_rgx.captures(line)
+            .ok_or_else(|| ParseError::at(line, line, "Sensor at x=<x>, y=<y>: closest beacon is at x=<x>, y=<y>"))?;
+        let number = |group: usize| parse_number::<i64>(line, c.get(group).unwrap().as_str());
         
-        SensorReading {
-            sensor: (c.get(1).unwrap().as_str().parse::<i32>().unwrap(), c.get(2).unwrap().as_str().parse::<i32>().unwrap()),
-            beacon: (c.get(3).unwrap().as_str().parse::<i32>().unwrap(), c.get(4).unwrap().as_str().parse::<i32>().unwrap()),
-        }
+        Ok(SensorReading {
+            sensor: Point::new(number(1)?, number(2)?),
+            beacon: Point::new(number(3)?, number(4)?),
+        })
     })
 }
 
-fn get_taxi_dist(reading: &SensorReading) -> i32 {
-    (reading.sensor.0 - reading.beacon.0).abs() + (reading.sensor.1 - reading.beacon.1).abs()
+/// All the sensors together, for questions about which cells they can see. A cell counts as covered
+/// if any sensor can see it, including cells with a beacon in.
+#[derive(Debug, Clone)]
+pub struct SensorField {
+    readings: Vec<SensorReading>
 }
 
-fn get_spots(reading: &SensorReading, y: i32, ranges: &mut Vec<(i32, i32)>) {
-    let base_range = get_range(&reading, y);
-    if reading.beacon.1 == y {
-        // combine_ranges((base_range.0, reading.beacon.0), ranges);
-        // combine_ranges((reading.beacon.0 + 1, base_range.1), ranges);
-        if reading.beacon.0 == base_range.0 {
-            combine_ranges((base_range.0 + 1, base_range.1), ranges);
+impl SensorField {
+    pub fn new(readings: Vec<SensorReading>) -> Self {
+        SensorField { readings }
+    }
+
+    pub fn readings(&self) -> &[SensorReading] {
+        &self.readings
+    }
+
+    pub fn covers(&self, point: Point) -> bool {
+        self.readings.iter().any(|reading| reading.covers(point))
+    }
+
+    pub fn sensors_covering(&self, point: Point) -> impl Iterator<Item = &SensorReading> + '_ {
+        self.readings.iter().filter(move |reading| reading.covers(point))
+    }
+
+    /// The covered cells of row `y`.
+    pub fn row(&self, y: i64) -> IntervalSet<i64> {
+        self.readings.iter().filter_map(|reading| reading.row_span(y)).collect()
+    }
+
+    /// The covered cells of each of `rows` in turn, worked out incrementally; see `RowSweep`.
+    pub fn sweep(&self, rows: RangeInclusive<i64>) -> RowSweep<'_> {
+        let (first, last) = (*rows.start(), *rows.end());
+        let mut pending: Vec<&SensorReading> = self.readings.iter()
+            .filter(|reading| reading.sensor.y + reading.reach() >= first)
+            .collect();
+        // Latest first row at the front, so the next sensor to come into reach is at the end
+        pending.sort_by_key(|reading| std::cmp::Reverse(reading.sensor.y - reading.reach()));
+        RowSweep { pending, active: vec![], y: first, last }
+    }
+
+    /// The covered cells of column `x`.
+    pub fn column(&self, x: i64) -> IntervalSet<i64> {
+        self.readings.iter().filter_map(|reading| reading.column_span(x)).collect()
+    }
+
+    /// Number of covered cells in the rectangle with corners `from` and `to`, both included. Goes
+    /// along whichever of its rows or columns there are fewer of.
+    pub fn covered_area(&self, from: Point, to: Point) -> i64 {
+        let (left, right) = (from.x.min(to.x), from.x.max(to.x));
+        let (top, bottom) = (from.y.min(to.y), from.y.max(to.y));
+        if bottom - top <= right - left {
+            let window = IntervalSet::from((left, right));
+            self.sweep(top..=bottom).map(|(_, row)| row.intersection(&window).total_length()).sum()
         } else {
-            combine_ranges((base_range.0 , base_range.1 - 1), ranges);
+            let window = IntervalSet::from((top, bottom));
+            (left..=right).map(|x| self.column(x).intersection(&window).total_length()).sum()
+        }
+    }
+}
+
+// A sensor's cells on the row a sweep is at
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    start: i64,
+    end: i64,
+    sensor_row: i64, // The span grows until this row and shrinks after it
+    last_row: i64
+}
+
+/// Streams the coverage of a band of rows, one row at a time, as `(y, covered cells)`.
+///
+/// Instead of going through every sensor for each row, the sweep keeps the spans of the sensors that
+/// reach the current row, sorted by where they start. Moving down a row widens each span above its
+/// sensor and narrows each one below it by a cell at either end; sensors join when their reach starts
+/// and drop out when it ends. The spans stay almost sorted, so keeping them in order is cheap.
+pub struct RowSweep<'a> {
+    pending: Vec<&'a SensorReading>,
+    active: Vec<Span>,
+    y: i64,
+    last: i64
+}
+
+impl RowSweep<'_> {
+    // Moves the spans from the previous row to `self.y` and brings in sensors that now reach it
+    fn advance(&mut self) {
+        let y = self.y;
+        self.active.retain(|span| span.last_row >= y);
+        for span in &mut self.active {
+            let change = if y <= span.sensor_row { 1 } else { -1 };
+            span.start -= change;
+            span.end += change;
+        }
+
+        while let Some(reading) = self.pending.last() {
+            if reading.sensor.y - reading.reach() > y {
+                break;
+            }
+            if let Some((start, end)) = reading.row_span(y) {
+                let last_row = reading.sensor.y + reading.reach();
+                self.active.push(Span { start, end, sensor_row: reading.sensor.y, last_row });
+            }
+            self.pending.pop();
+        }
+
+        // Insertion sort, which is quick on a list that is nearly in order already
+        for i in 1..self.active.len() {
+            let mut j = i;
+            while j > 0 && self.active[j - 1].start > self.active[j].start {
+                self.active.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+    }
+}
+
+impl Iterator for RowSweep<'_> {
+    type Item = (i64, IntervalSet<i64>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.y > self.last {
+            return None;
+        }
+
+        self.advance();
+        let row = self.active.iter().map(|span| (span.start, span.end)).collect();
+        let y = self.y;
+        self.y += 1;
+        Some((y, row))
+    }
+}
+
+/// Ways of finding the distress beacon in part 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Works on the sensor diamonds turned 45° into squares; fast whatever the size of the search area.
+    Rotated,
+    /// Checks the coverage of every row in turn until one has a gap.
+    BruteForce,
+    /// Like `BruteForce`, with the rows shared out between one thread per core.
+    Parallel
+}
+
+algorithms!(Algorithm { Rotated => "rotated", BruteForce => "brute-force", Parallel => "parallel" });
+
+impl Solution for Day15 {
+    type Input = SensorField;
+    type Part1 = i64;
+    type Part2 = Option<i64>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError> {
+        Ok(SensorField::new(read_input(contents)?))
+    }
+
+    fn part1(field: &Self::Input) -> i64 {
+        solve_part1(field, &Params::PUZZLE) // 4560025
+    }
+
+    fn part2(field: &Self::Input) -> Option<i64> {
+        solve_part2(field, &Params::PUZZLE, Algorithm::Rotated) // 12480406634249
+    }
+
+    const ALGORITHMS: &'static [&'static str] = &Algorithm::NAMES;
+
+    const PARAMETERS: &'static [(&'static str, i64)] = &[
+        ("row", Params::PUZZLE.row),
+        ("bound", Params::PUZZLE.bound),
+        ("multiplier", Params::PUZZLE.multiplier)
+    ];
+
+    fn part1_with(field: &Self::Input, settings: &Settings) -> i64 {
+        solve_part1(field, &Params::from_settings(settings))
+    }
+
+    fn part2_with(field: &Self::Input, settings: &Settings) -> Option<i64> {
+        solve_part2(field, &Params::from_settings(settings), Algorithm::from_settings(settings).unwrap())
+    }
+
+    fn check_settings(settings: &Settings) -> Result<(), String> {
+        Params::from_settings(settings).check()
+    }
+}
+
+// Covered cells minus the beacons on the row, which are always within range of their sensor
+pub fn solve_part1(field: &SensorField, params: &Params) -> i64 {
+    let row = params.row;
+    let beacons: HashSet<Point> = field.readings().iter().map(|reading| reading.beacon).filter(|beacon| beacon.y == row).collect();
+    field.row(row).total_length() - beacons.len() as i64
+}
+
+/// The distress beacon's tuning frequency, or `None` if every cell of the square is covered or the
+/// frequency doesn't fit in an `i64`.
+pub fn solve_part2(field: &SensorField, params: &Params, algorithm: Algorithm) -> Option<i64> {
+    let beacon = match algorithm {
+        Algorithm::Rotated => first_uncovered_cell_rotated(field, params.bound),
+        Algorithm::BruteForce => first_uncovered_cell(field, params.bound),
+        Algorithm::Parallel => first_uncovered_cell_parallel(field, params.bound, threads())
+    };
+    let beacon = beacon?;
+    beacon.x.checked_mul(params.multiplier)?.checked_add(beacon.y)
+}
+
+// Row by row, the first cell of the 0..=bound square that no sensor can see
+fn first_uncovered_cell(field: &SensorField, bound: i64) -> Option<Point> {
+    let search = IntervalSet::from((0, bound));
+    field.sweep(0..=bound).find_map(|(y, row)| {
+        search.difference(&row).intervals().first().map(|&(x, _)| Point::new(x, y))
+    })
+}
+
+// Rows a thread takes at a time
+const CHUNK: i64 = 10_000;
+
+fn threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+// Like `first_uncovered_cell`, with each thread taking the next chunk of rows until there are none left.
+// Once a gap is found, rows below it are no longer worth looking at, so the threads stop there.
+fn first_uncovered_cell_parallel(field: &SensorField, bound: i64, threads: usize) -> Option<Point> {
+    let search = IntervalSet::from((0, bound));
+    let next = AtomicI64::new(0);
+    let found = AtomicI64::new(i64::MAX);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let (search, next, found) = (&search, &next, &found);
+            scope.spawn(move || loop {
+                let start = next.fetch_add(CHUNK, Ordering::Relaxed);
+                if start > bound || start > found.load(Ordering::Relaxed) {
+                    break;
+                }
+                for (y, row) in field.sweep(start..=(start + CHUNK - 1).min(bound)) {
+                    if y > found.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if !search.is_subset(&row) {
+                        found.fetch_min(y, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            });
         }
-    } else {
-        combine_ranges(base_range, ranges);
+    });
+
+    let y = found.into_inner();
+    if y == i64::MAX {
+        return None;
     }
+    search.difference(&field.row(y)).intervals().first().map(|&(x, _)| Point::new(x, y))
 }
 
-fn get_range(reading: &SensorReading, y: i32) -> (i32, i32) {
-    let y_dist = (reading.sensor.1 - y).abs();
-    let taxi_dist = get_taxi_dist(&reading);
-    let r = max(0, taxi_dist - y_dist);
+/// The merged coverage of each of `rows` in order, worked out on `threads` threads.
+pub fn coverage_of_rows(field: &SensorField, rows: RangeInclusive<i64>, threads: usize) -> Vec<IntervalSet<i64>> {
+    let (first, last) = (*rows.start(), *rows.end());
+    if first > last {
+        return vec![];
+    }
+    // Each thread gets a run of rows next to each other, so the results only need joining up
+    let per_thread = ((last - first) / threads.max(1) as i64) + 1;
 
-    (reading.sensor.0-r, reading.sensor.0+r) // +1 cause end exclusive
+    thread::scope(|scope| {
+        let handles: Vec<_> = (first..=last).step_by(per_thread as usize)
+            .map(|start| {
+                let end = (start + per_thread - 1).min(last);
+                scope.spawn(move || field.sweep(start..=end).map(|(_, row)| row).collect::<Vec<_>>())
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
 }
 
-fn merge_range(range: (i32, i32), other: (i32, i32)) -> (i32, i32) {
-    (min(range.0, other.0), max(range.1, other.1))
+// A rectangle of the plane turned 45° that no sensor covers: the cells with x + y in `u` and x - y in `v`
+struct Region {
+    u: (i64, i64),
+    v: (i64, i64)
 }
 
-fn combine_ranges(mut r: (i32, i32), ranges: &mut Vec<(i32, i32)>) {
-    //println!("{:?} -> {:?}", r, ranges);
-    if r.0 == r.1 {
-        return;
+impl Region {
+    // The rows with cells that are in both the region and the 0..=bound square
+    fn rows(&self, bound: i64) -> RangeInclusive<i64> {
+        let ((u0, u1), (v0, v1)) = (self.u, self.v);
+        let first = [0, -(v1 - u0).div_euclid(2), u0 - bound, -v1].into_iter().max().unwrap();
+        let last = [bound, (u1 - v0).div_euclid(2), bound - v0, u1].into_iter().min().unwrap();
+        first..=last
     }
-    
-    if ranges.is_empty() {
-        ranges.push(r);
-        return;
+
+    // The cells of row `y` in both the region and the square; only call with one of `rows`
+    fn row(&self, y: i64, bound: i64) -> (i64, i64) {
+        ((self.u.0 - y).max(self.v.0 + y).max(0), (self.u.1 - y).min(self.v.1 + y).min(bound))
     }
-    
-    let mut merge_start: i32 = -1;
-    let mut merge_end: i32 = -1;
-    for i in 0..ranges.len() {
-        let current = ranges[i];
-        if r.1 - current.0 < -1 {
-            if merge_start < 0 {
-                ranges.insert(i, r);
+}
+
+/// With u = x + y and v = x - y each sensor's diamond becomes a square, so the edges of the squares
+/// cut the (u, v) plane into a grid of rectangles, each either fully covered or not covered at all.
+/// Only the uncovered rectangles matter, so the work depends on the number of sensors rather than on
+/// `bound`.
+fn uncovered_regions(field: &SensorField, bound: i64) -> Vec<Region> {
+    // (u, v) of each sensor with its reach
+    let squares: Vec<(i64, i64, i64)> = field.readings().iter().map(|reading| {
+        let Point { x, y } = reading.sensor;
+        (x + y, x - y, reading.reach())
+    }).collect();
+
+    // The square spans u in 0..=2 * bound and v in -bound..=bound; rectangles run from one cut to
+    // just before the next
+    let cuts = |first: i64, last: i64, centre: fn(&(i64, i64, i64)) -> i64| -> Vec<i64> {
+        let mut cuts: Vec<i64> = squares.iter()
+            .flat_map(|square| [centre(square) - square.2, centre(square) + square.2 + 1])
+            .chain([first, last + 1])
+            .filter(|cut| (first..=last + 1).contains(cut))
+            .collect();
+        cuts.sort_unstable();
+        cuts.dedup();
+        cuts
+    };
+    let u_cuts = cuts(0, 2 * bound, |square| square.0);
+    let v_cuts = cuts(-bound, bound, |square| square.1);
+
+    let mut regions = vec![];
+    for us in u_cuts.windows(2) {
+        for vs in v_cuts.windows(2) {
+            let covered = squares.iter().any(|&(u, v, r)| (us[0] - u).abs() <= r && (vs[0] - v).abs() <= r);
+            let region = Region { u: (us[0], us[1] - 1), v: (vs[0], vs[1] - 1) };
+            if !covered && !region.rows(bound).is_empty() {
+                regions.push(region);
             }
-            break;
-        } else if r.0 - current.1 > 1 {
-            if i == ranges.len() - 1 {
-                ranges.push(r);
+        }
+    }
+    regions
+}
+
+// The first uncovered cell by row and then column, without going through the others
+fn first_uncovered_cell_rotated(field: &SensorField, bound: i64) -> Option<Point> {
+    uncovered_regions(field, bound).iter()
+        .map(|region| {
+            let y = *region.rows(bound).start();
+            Point::new(region.row(y, bound).0, y)
+        })
+        .min_by_key(|cell| (cell.y, cell.x))
+}
+
+/// Every cell of the 0..=bound square that no sensor can see, ordered by row and then column.
+pub fn uncovered_cells(field: &SensorField, bound: i64) -> Vec<Point> {
+    let mut cells = vec![];
+    for region in uncovered_regions(field, bound) {
+        for y in region.rows(bound) {
+            let (first, last) = region.row(y, bound);
+            cells.extend((first..=last).map(|x| Point::new(x, y)));
+        }
+    }
+
+    cells.sort_by_key(|cell| (cell.y, cell.x));
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r"
+Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3
+";
+
+    #[test]
+    fn part1_example() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&input, &Params::EXAMPLE), 26);
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        for algorithm in Algorithm::ALL {
+            assert_eq!(solve_part2(&input, &Params::EXAMPLE, algorithm), Some(56000011), "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn settings_override_puzzle_values() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        let params = [("row", 10), ("bound", 20), ("multiplier", 10)];
+        let settings = Settings {
+            params: params.iter().map(|(name, value)| (name.to_string(), *value)).collect(),
+            ..Settings::default()
+        };
+        assert_eq!(Params::from_settings(&Settings::default()), Params::PUZZLE);
+        assert_eq!((Day15::part1_with(&input, &settings), Day15::part2_with(&input, &settings)), (26, Some(151)));
+    }
+
+    #[test]
+    fn part2_without_a_gap_or_frequency() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        let params = |bound, multiplier| Params { bound, multiplier, ..Params::EXAMPLE };
+        for algorithm in Algorithm::ALL {
+            assert_eq!(solve_part2(&input, &params(5, 4_000_000), algorithm), None, "{}", algorithm.name());
+            assert_eq!(solve_part2(&input, &params(20, -1), algorithm), Some(-3));
+            assert_eq!(solve_part2(&input, &params(20, i64::MAX), algorithm), None);
+        }
+
+        assert!(params(-1, 1).check().is_err());
+        assert!(params(0, 1).check().is_ok());
+    }
+
+    #[test]
+    fn sensor_field_queries_agree() {
+        let field = Day15::parse(EXAMPLE).unwrap();
+        let sensor = Point::new(8, 7);
+        assert!(field.sensors_covering(sensor).any(|reading| reading.sensor == sensor));
+        assert!(!field.covers(Point::new(14, 11)));
+        assert_eq!(field.sensors_covering(Point::new(14, 11)).count(), 0);
+
+        let mut count = 0;
+        for y in -10..=30 {
+            for x in -10..=30 {
+                let point = Point::new(x, y);
+                let covered = field.covers(point);
+                assert_eq!(field.row(y).contains(x), covered, "{}", point);
+                assert_eq!(field.column(x).contains(y), covered, "{}", point);
+                assert_eq!(field.sensors_covering(point).next().is_some(), covered, "{}", point);
+                count += covered as i64;
             }
-        } else {
-            if merge_start < 0 {
-                merge_start = i as i32;
+        }
+        assert_eq!(field.covered_area(Point::new(-10, -10), Point::new(30, 30)), count);
+        // Taller than wide, so counted by column
+        let column = |x: i64| (-10..=30).filter(|y| field.covers(Point::new(x, *y))).count() as i64;
+        assert_eq!(field.covered_area(Point::new(14, 30), Point::new(13, -10)), column(13) + column(14));
+    }
+
+    #[test]
+    fn sweep_matches_row_by_row() {
+        let field = Day15::parse(EXAMPLE).unwrap();
+        for first in [-20, -3, 0, 10, 29] {
+            let rows: Vec<(i64, IntervalSet<i64>)> = field.sweep(first..=40).collect();
+            assert_eq!(rows.len() as i64, 41 - first);
+            for (y, row) in rows {
+                assert_eq!(row, field.row(y), "row {} sweeping from {}", y, first);
             }
-            merge_end = i as i32;
-            
-            r = merge_range(r, current);
-            //println!("Merged range {:?}", r);
-        }
-    }
-    
-    //println!("{}, {}", merge_start, merge_end);
-    //     _ _
-    // 0 1 2 3 4 5
-    if merge_start >= 0 {
-        for i in merge_start..merge_end+1 {
-            ranges.remove(merge_start as usize);
-        }
-        ranges.insert(merge_start as usize, r);
-    }
-}
-
-fn test_cr() {
-    let mergee_1 = (1,1);
-    let mut ranges_1 = vec![(1, 1)];
-    let expected_1 = vec![(1, 1)];
-    combine_ranges(mergee_1, &mut ranges_1);
-    assert_eq!(ranges_1, expected_1);
-
-    let mergee_2 = (2,3);
-    let mut ranges_2 = vec![(5, 7)];
-    let expected_2 = vec![(2, 3), (5, 7)];
-    combine_ranges(mergee_2, &mut ranges_2);
-    assert_eq!(ranges_2, expected_2);
-
-    let mergee_3 = (2,5);
-    let mut ranges_3 = vec![(5, 7)];
-    let expected_3 = vec![(2, 7)];
-    combine_ranges(mergee_3, &mut ranges_3);
-    assert_eq!(ranges_3, expected_3);
-
-    let mergee_3 = (2,5);
-    let mut ranges_3 = vec![(5, 7)];
-    let expected_3 = vec![(2, 7)];
-    combine_ranges(mergee_3, &mut ranges_3);
-    assert_eq!(ranges_3, expected_3);
-
-    let mergee_4 = (3,4);
-    let mut ranges_4 = vec![(2, 3), (4, 7)];
-    let expected_4 = vec![(2, 7)];
-    combine_ranges(mergee_4, &mut ranges_4);
-    assert_eq!(ranges_4, expected_4);
-
-    let mergee_5 = (9,10);
-    let mut ranges_5 = vec![(2, 3), (4, 7)];
-    let expected_5 = vec![(2, 3), (4, 7), (9, 10)];
-    combine_ranges(mergee_5, &mut ranges_5);
-    assert_eq!(ranges_5, expected_5);
-
-    let mergee_6 = (2,2);
-    let mut ranges_6 = vec![(2, 3), (4, 7)];
-    let expected_6 = vec![(2, 3), (4, 7)];
-    combine_ranges(mergee_6, &mut ranges_6);
-    assert_eq!(ranges_6, expected_6);
-    
-    // (3070241, 3485039) -> [(2913315, 3549817), (3846541, 4121117)]
-    let mergee_7 = (3070241, 3485039);
-    let mut ranges_7 = vec![(2913315, 3549817), (3846541, 4121117)];
-    let expected_7 = vec![(2913315, 3549817), (3846541, 4121117)];
-    combine_ranges(mergee_7, &mut ranges_7);
-    assert_eq!(ranges_7, expected_7);
-}
-
-pub fn solve() {
-    let elapsed = SystemTime::now();
-    let mut input = read_input();
-
-    //test_cr();
-    
-    let part1 = solve_part1(&input);
-    let part2 = solve_part2(&input);
-
-    println!("Day 15");
-    println!("Part 1: {}", part1); // 4560025
-    println!("Part 2: {}", part2); // 12480406634249
-    println!("Elapsed time: {}ms", elapsed.elapsed().unwrap().as_millis());
-}
-
-fn solve_part1(readings: &Vec<SensorReading>) -> i32 {
-    let mut ranges = vec![];
-    for r in readings {
-        get_spots(&r, 2000000, &mut ranges);
-    }
-    ranges.iter().map(|r| r.1 - r.0 + 1).sum()
-}
-
-fn solve_part2(readings: &Vec<SensorReading>) -> u64 {
-    for y in 0..4_000_000+1 {
-        let mut ranges = vec![get_range(&readings[0], y)];
-        for i in 1..readings.len() {
-            //println!("{:?} --> {:?}", get_range(&readings[i], y), ranges);
-            combine_ranges(get_range(&readings[i], y), &mut ranges);
-        }
-        //println!("{:?}", ranges);
-        if ranges.len() > 1 {
-            return (ranges[0].1 + 1) as u64 * 4_000_000 + y as u64;
-        }
+        }
+
+        // Excluded cells across a band, as part 1 counts them for one row
+        let excluded: i64 = field.sweep(0..=20).map(|(_, row)| row.total_length()).sum();
+        assert_eq!(excluded, (0..=20).map(|y| field.row(y).total_length()).sum());
     }
 
-    panic!("No result for part 2");
+    #[test]
+    fn coverage_of_rows_in_order() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        let expected: Vec<IntervalSet<i64>> = (-5..=25).map(|y| input.row(y)).collect();
+        for threads in [1, 3, 8, 100] {
+            assert_eq!(coverage_of_rows(&input, -5..=25, threads), expected, "{} threads", threads);
+        }
+        assert_eq!(coverage_of_rows(&input, 10..=10, 4)[0].intervals(), &[(-2, 24)]);
+    }
+
+    #[test]
+    fn parallel_search_finds_first_gap() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        for threads in [1, 2, 5] {
+            assert_eq!(first_uncovered_cell_parallel(&input, 20, threads), Some(Point::new(14, 11)));
+            assert_eq!(first_uncovered_cell_parallel(&input, 30, threads), first_uncovered_cell(&input, 30));
+        }
+    }
+
+    // Every cell the brute-force scan leaves uncovered, compared over a larger area
+    #[test]
+    fn uncovered_cells_match_scan() {
+        let input = Day15::parse(EXAMPLE).unwrap();
+        assert_eq!(uncovered_cells(&input, 20), vec![Point::new(14, 11)]);
+
+        let bound = 30;
+        let search = IntervalSet::from((0, bound));
+        let scanned: Vec<Point> = (0..=bound)
+            .flat_map(|y| {
+                let gaps = search.difference(&input.row(y));
+                gaps.intervals().iter().flat_map(|&(start, end)| start..=end).map(|x| Point::new(x, y)).collect::<Vec<_>>()
+            })
+            .collect();
+        assert!(scanned.len() > 1);
+        assert_eq!(uncovered_cells(&input, bound), scanned);
+    }
 }
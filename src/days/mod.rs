@@ -1,3 +1,41 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::answer::Answer;
+use crate::settings::Settings;
+use crate::timing::{self, Timing};
+use crate::utility::parse_error::ParseError;
+
+/// For days with more than one algorithm: `ALL`, `NAMES`, `name`, `from_settings` and `FromStr` for an
+/// enum of them, given each variant's name on the command line. The first is the default.
+macro_rules! algorithms {
+    ($algorithm:ident { $($variant:ident => $name:literal),+ }) => {
+        impl $algorithm {
+            pub const ALL: [$algorithm; [$($name),+].len()] = [$($algorithm::$variant),+];
+            pub const NAMES: [&'static str; [$($name),+].len()] = [$($name),+];
+
+            pub fn name(self) -> &'static str {
+                match self {
+                    $($algorithm::$variant => $name),+
+                }
+            }
+
+            /// The algorithm `settings` names, the default if none.
+            pub fn from_settings(settings: &$crate::settings::Settings) -> Result<$algorithm, String> {
+                settings.algorithm.as_deref().map_or(Ok($algorithm::ALL[0]), str::parse)
+            }
+        }
+
+        impl std::str::FromStr for $algorithm {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $algorithm::ALL.into_iter().find(|algorithm| algorithm.name() == s)
+                    .ok_or_else(|| format!("Unknown algorithm: {} (expected {})", s, $algorithm::NAMES.join(", ")))
+            }
+        }
+    };
+}
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -13,3 +51,170 @@ pub mod day12;
 pub mod day13;
 pub mod day14;
 pub mod day15;
+
+/// A single puzzle: turns the raw input into `Input` once, then answers both parts from it.
+pub trait Solution {
+    type Input;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn parse(contents: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+
+    /// Names of the ways the day can be solved, for days that have more than one. `part1` and `part2`
+    /// use the first.
+    const ALGORITHMS: &'static [&'static str] = &[];
+
+    /// Numbers from the puzzle text that can be changed, e.g. to run the examples, with the puzzle's
+    /// values as defaults.
+    const PARAMETERS: &'static [(&'static str, i64)] = &[];
+
+    /// `part1` with a different algorithm or parameters. `settings` are expected to have passed
+    /// `Day::check`; a day may panic on ones that don't.
+    fn part1_with(input: &Self::Input, _settings: &Settings) -> Self::Part1 {
+        Self::part1(input)
+    }
+
+    /// `part2` with a different algorithm or parameters.
+    fn part2_with(input: &Self::Input, _settings: &Settings) -> Self::Part2 {
+        Self::part2(input)
+    }
+
+    /// Rejects parameter values the day can't work with, before anything is run.
+    fn check_settings(_settings: &Settings) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2")
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Unknown part: {} (expected 1 or 2)", s))
+        }
+    }
+}
+
+type Runner = fn(&str, &[Part], &Settings) -> Result<Vec<(Part, Answer)>, ParseError>;
+type Timer = fn(&str, usize, &Settings) -> Result<Timing, ParseError>;
+type Checker = fn(&Settings) -> Result<(), String>;
+
+/// Registry entry for one day, with the `Solution` erased so all days fit in one table.
+pub struct Day {
+    pub number: u8,
+    pub title: &'static str,
+    pub algorithms: &'static [&'static str],
+    pub parameters: &'static [(&'static str, i64)],
+    run: Runner,
+    time: Timer,
+    check_settings: Checker
+}
+
+impl Day {
+    const fn new<S: Solution>(number: u8, title: &'static str) -> Self {
+        Day {
+            number,
+            title,
+            algorithms: S::ALGORITHMS,
+            parameters: S::PARAMETERS,
+            run: run_solution::<S>,
+            time: timing::time_solution::<S>,
+            check_settings: S::check_settings
+        }
+    }
+
+    pub fn input_path(&self) -> String {
+        format!("inputs/day{:02}.txt", self.number)
+    }
+
+    /// Parses `contents` once and answers the requested parts in order.
+    pub fn run(&self, contents: &str, parts: &[Part], settings: &Settings) -> Result<Vec<(Part, Answer)>, ParseError> {
+        (self.run)(contents, parts, settings)
+    }
+
+    /// Times parse, part 1 and part 2 separately over `runs` runs.
+    pub fn time(&self, contents: &str, runs: usize, settings: &Settings) -> Result<Timing, ParseError> {
+        (self.time)(contents, runs, settings)
+    }
+
+    /// Whether the day has the algorithm and parameters `settings` asks for.
+    pub fn check(&self, settings: &Settings) -> Result<(), String> {
+        if let Some(algorithm) = &settings.algorithm {
+            if self.algorithms.is_empty() {
+                return Err(format!("Day {} has only one algorithm", self.number));
+            }
+            if !self.algorithms.contains(&algorithm.as_str()) {
+                return Err(format!("Unknown algorithm for day {}: {} (expected {})",
+                                   self.number, algorithm, self.algorithms.join(", ")));
+            }
+        }
+
+        for name in settings.params.keys() {
+            if !self.parameters.iter().any(|(param, _)| param == name) {
+                let names: Vec<&str> = self.parameters.iter().map(|(param, _)| *param).collect();
+                return Err(match names.is_empty() {
+                    true => format!("Day {} has no parameters", self.number),
+                    false => format!("Unknown parameter for day {}: {} (expected {})", self.number, name, names.join(", "))
+                });
+            }
+        }
+
+        (self.check_settings)(settings)
+    }
+}
+
+fn run_solution<S: Solution>(contents: &str, parts: &[Part], settings: &Settings) -> Result<Vec<(Part, Answer)>, ParseError> {
+    let input = S::parse(contents)?;
+    Ok(parts.iter().map(|part| {
+        let answer = match part {
+            Part::One => S::part1_with(&input, settings).into(),
+            Part::Two => S::part2_with(&input, settings).into()
+        };
+        (*part, answer)
+    }).collect())
+}
+
+pub const DAYS: [Day; 15] = [
+    Day::new::<day01::Day01>(1, "Calorie Counting"),
+    Day::new::<day02::Day02>(2, "Rock Paper Scissors"),
+    Day::new::<day03::Day03>(3, "Rucksack Reorganization"),
+    Day::new::<day04::Day04>(4, "Camp Cleanup"),
+    Day::new::<day05::Day05>(5, "Supply Stacks"),
+    Day::new::<day06::Day06>(6, "Tuning Trouble"),
+    Day::new::<day07::Day07>(7, "No Space Left On Device"),
+    Day::new::<day08::Day08>(8, "Treetop Tree House"),
+    Day::new::<day09::Day09>(9, "Rope Bridge"),
+    Day::new::<day10::Day10>(10, "Cathode-Ray Tube"),
+    Day::new::<day11::Day11>(11, "Monkey in the Middle"),
+    Day::new::<day12::Day12>(12, "Hill Climbing Algorithm"),
+    Day::new::<day13::Day13>(13, "Distress Signal"),
+    Day::new::<day14::Day14>(14, "Regolith Reservoir"),
+    Day::new::<day15::Day15>(15, "Beacon Exclusion Zone"),
+];
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
@@ -0,0 +1,8 @@
+extern crate core;
+
+pub mod answer;
+pub mod days;
+pub mod settings;
+pub mod timing;
+pub mod utility;
+pub mod verify;
@@ -1,25 +1,21 @@
-extern crate core;
+use std::env;
+use std::process::exit;
 
-pub mod days;
-mod utility;
+mod cli;
 
 fn main() {
-    // days::day01::solve();
-    // days::day02::solve();
-    // days::day03::solve();
-    // days::day04::solve();
-    // days::day05::solve();
-    // days::day06::solve();
-    // days::day07::solve();
-    // days::day08::solve();
-    // days::day09::solve();
-    // days::day10::solve();
-    // days::day11::solve();
-    // days::day12::solve();
-    // days::day13::solve();
-    //days::day14::solve();
-    days::day15::solve();
-}
-
+    let args: Vec<String> = env::args().skip(1).collect();
 
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, cli::USAGE);
+            exit(2);
+        }
+    };
 
+    if let Err(error) = cli::execute(command) {
+        eprintln!("{}", error);
+        exit(1);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use crate::days::Solution;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{numbered_lines, parse_number};
+
+/// Choices that change how a day is solved, from the command line or a config file. Anything not
+/// set keeps the puzzle's value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settings {
+    /// One of the day's `ALGORITHMS`.
+    pub algorithm: Option<String>,
+    /// Values for some of the day's `PARAMETERS`, by name.
+    pub params: BTreeMap<String, i64>
+}
+
+impl Settings {
+    /// The value of one of `S::PARAMETERS`, or its default if it wasn't set.
+    pub fn param<S: Solution>(&self, name: &str) -> i64 {
+        match self.params.get(name) {
+            Some(value) => *value,
+            None => S::PARAMETERS.iter().find(|(param, _)| *param == name)
+                .unwrap_or_else(|| panic!("No parameter called {}", name)).1
+        }
+    }
+}
+
+/// One line of a config file: `<day> <parameter> <value>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    pub day: u8,
+    pub name: String,
+    pub value: i64
+}
+
+/// Blank lines and lines starting with `#` are ignored.
+pub fn parse_config(contents: &str) -> Result<Vec<ConfigEntry>, ParseError> {
+    numbered_lines(contents)
+        .filter(|(_, line)| !line.starts_with('#'))
+        .map(|(i, line)| parse_entry(line).map_err(|error| error.offset(i)))
+        .collect()
+}
+
+fn parse_entry(line: &str) -> Result<ConfigEntry, ParseError> {
+    let mut fields = line.split_whitespace();
+    let mut field = |expected: &str| fields.next().ok_or_else(|| ParseError::at(line, &line[line.len()..], expected));
+
+    let day = field("a day")?;
+    let name = field("a parameter name")?;
+    let value = field("a value")?;
+    if let Some(extra) = fields.next() {
+        return Err(ParseError::at(line, extra, "end of line"));
+    }
+
+    Ok(ConfigEntry { day: parse_number(line, day)?, name: name.to_string(), value: parse_number(line, value)? })
+}
+
+/// `name=value`, as given to `--param`.
+pub fn parse_param(text: &str) -> Result<(String, i64), String> {
+    let (name, value) = text.split_once('=').ok_or(format!("Expected NAME=VALUE, found {}", text))?;
+    let value = value.parse().map_err(|_| format!("Invalid value for {}: {}", name, value))?;
+    Ok((name.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_lines() {
+        let config = parse_config("# example sizes\n15 row 10\n\n15 bound 20\n").unwrap();
+        assert_eq!(config, vec![
+            ConfigEntry { day: 15, name: "row".to_string(), value: 10 },
+            ConfigEntry { day: 15, name: "bound".to_string(), value: 20 }
+        ]);
+
+        let error = parse_config("15 row 10\n15 row ten").unwrap_err();
+        assert_eq!((error.line, error.found.as_str()), (2, "ten"));
+        assert!(parse_config("15 row").is_err());
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(parse_param("row=-3"), Ok(("row".to_string(), -3)));
+        assert!(parse_param("row").is_err());
+        assert!(parse_param("row=x").is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::{Display, Formatter};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use crate::days::Solution;
+use crate::settings::Settings;
+use crate::utility::parse_error::ParseError;
+
+/// Summary of repeated measurements of one step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub mean: Duration,
+    pub stddev: Duration
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats { min: Duration::ZERO, mean: Duration::ZERO, stddev: Duration::ZERO };
+        }
+
+        let nanos: Vec<f64> = samples.iter().map(|sample| sample.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / nanos.len() as f64;
+        let variance = nanos.iter().map(|n| (n - mean).powi(2)).sum::<f64>() / nanos.len() as f64;
+
+        Stats {
+            min: *samples.iter().min().unwrap(),
+            mean: Duration::from_nanos(mean as u64),
+            stddev: Duration::from_nanos(variance.sqrt() as u64)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timing {
+    pub runs: usize,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats
+}
+
+impl Timing {
+    /// Mean time of a whole run: parse plus both parts.
+    pub fn total(&self) -> Duration {
+        self.parse.mean + self.part1.mean + self.part2.mean
+    }
+}
+
+/// Parses `contents` and answers both parts `runs` times, timing each step on its own.
+pub fn time_solution<S: Solution>(contents: &str, runs: usize, settings: &Settings) -> Result<Timing, ParseError> {
+    let mut parse = vec![];
+    let mut part1 = vec![];
+    let mut part2 = vec![];
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        let input = S::parse(black_box(contents))?;
+        parse.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part1_with(black_box(&input), settings));
+        part1.push(start.elapsed());
+
+        let start = Instant::now();
+        black_box(S::part2_with(black_box(&input), settings));
+        part2.push(start.elapsed());
+    }
+
+    Ok(Timing {
+        runs,
+        parse: Stats::from_samples(&parse),
+        part1: Stats::from_samples(&part1),
+        part2: Stats::from_samples(&part2)
+    })
+}
+
+/// A duration in the largest unit that keeps it above 1, e.g. `12.3µs` or `1.52s`.
+pub struct Pretty(pub Duration);
+
+impl Display for Pretty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let nanos = self.0.as_nanos() as f64;
+        let text = if nanos < 1e3 {
+            format!("{}ns", nanos)
+        } else if nanos < 1e6 {
+            format!("{:.1}µs", nanos / 1e3)
+        } else if nanos < 1e9 {
+            format!("{:.2}ms", nanos / 1e6)
+        } else {
+            format!("{:.2}s", nanos / 1e9)
+        };
+        f.pad(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_samples() {
+        let samples = [2, 4, 4, 4, 5, 5, 7, 9].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_millis(2));
+    }
+}
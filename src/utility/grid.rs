@@ -0,0 +1,217 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use crate::utility::parse_error::ParseError;
+use crate::utility::point::Point;
+use crate::utility::utils::numbered_lines;
+
+/// A rectangular grid stored row by row. Positions are `(x, y)` with `(0, 0)` in the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where T: Clone {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// None if the rows don't all have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    /// One cell per character of each non-blank line; `cell_fn` rejects a character by returning None,
+    /// which is reported as not being `expected`.
+    pub fn parse<F>(contents: &str, expected: &str, mut cell_fn: F) -> Result<Self, ParseError>
+    where F: FnMut(char) -> Option<T> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (i, line) in numbered_lines(contents) {
+            let before = cells.len();
+            for (ci, c) in line.char_indices() {
+                let cell = cell_fn(c)
+                    .ok_or_else(|| ParseError::at(line, &line[ci..ci + c.len_utf8()], expected).offset(i))?;
+                cells.push(cell);
+            }
+
+            let row_width = cells.len() - before;
+            match width {
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(ParseError::at(line, line, format!("a row of width {}", width)).offset(i));
+                },
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> Option<&T> {
+        if self.contains(pos) { Some(&self.cells[pos.1 * self.width + pos.0]) } else { None }
+    }
+
+    /// Like `get`, for a point that may also be left of or above the grid.
+    pub fn at(&self, point: Point) -> Option<&T> {
+        point.to_usize().and_then(|pos| self.get(pos))
+    }
+
+    pub fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut T> {
+        if self.contains(pos) { Some(&mut self.cells[pos.1 * self.width + pos.0]) } else { None }
+    }
+
+    /// Returns false, and changes nothing, if `pos` is outside the grid.
+    pub fn set(&mut self, pos: (usize, usize), value: T) -> bool {
+        match self.get_mut(pos) {
+            Some(cell) => {
+                *cell = value;
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of range for width {}", x, self.width);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn position<P>(&self, mut predicate: P) -> Option<(usize, usize)>
+    where P: FnMut(&T) -> bool {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(pos, _)| pos)
+    }
+
+    /// `Point::neighbours4` of `pos`, leaving out those outside the grid.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.inside(Point::from(pos).neighbours4())
+    }
+
+    /// `Point::neighbours8` of `pos`, leaving out those outside the grid.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.inside(Point::from(pos).neighbours8())
+    }
+
+    fn inside<const N: usize>(&self, points: [Point; N]) -> impl Iterator<Item = (usize, usize)> + '_ {
+        points.into_iter().filter_map(|point| point.to_usize()).filter(|pos| self.contains(*pos))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where F: FnMut(&T) -> U {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| panic!("{:?} is outside the {}x{} grid", pos, width, height))
+    }
+}
+
+// Rows on separate lines, without a line break after the last one
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(contents: &str) -> Grid<u32> {
+        Grid::parse(contents, "a digit", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        let grid = digits("123\n456\r\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.to_string(), "123\n456");
+    }
+
+    #[test]
+    fn parse_rejects_ragged_rows_and_bad_cells() {
+        let error = Grid::parse("12\n345", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line, error.expected.as_str()), (2, "a row of width 2"));
+
+        let error = Grid::parse("12\n3x", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 2, "x"));
+    }
+
+    #[test]
+    fn get_and_set_are_bounds_checked() {
+        let mut grid = digits("12\n34");
+        assert_eq!(grid.get((2, 0)), None);
+        assert!(!grid.set((0, 2), 9));
+        assert!(grid.set((1, 0), 9));
+        assert_eq!(grid.row(0), &[1, 9]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![9, 4]);
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid = digits("123\n456\n789");
+        assert_eq!(grid.neighbours4((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (1, 1), (0, 1)]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt::Debug;
+use std::ops::{Add, Sub};
+
+/// Integer types an `IntervalSet` can hold.
+pub trait Integer: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> + From<u8> {}
+
+impl<T> Integer for T
+where T: Copy + Ord + Debug + Add<Output = T> + Sub<Output = T> + From<u8> {}
+
+fn one<T: Integer>() -> T {
+    T::from(1)
+}
+
+/// A set of integers stored as sorted, inclusive `(start, end)` intervals. Intervals that overlap or
+/// touch are merged, so there is always a gap between one interval and the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<(T, T)>
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    pub fn intervals(&self) -> &[(T, T)] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Adds `start..=end`; does nothing if `start > end`.
+    pub fn insert(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        // Intervals from `first` up to `last` overlap or touch the new one. Each `+ one()` or `- one()`
+        // only happens on the side where there is room, so intervals can end at the type's limits.
+        let first = self.intervals.partition_point(|&(_, b)| b < start && b + one() < start);
+        let last = self.intervals.partition_point(|&(a, _)| a <= end || a - one() <= end);
+        let merged = if first < last {
+            (start.min(self.intervals[first].0), end.max(self.intervals[last - 1].1))
+        } else {
+            (start, end)
+        };
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Takes out `start..=end`; does nothing if `start > end`.
+    pub fn remove(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|&(_, b)| b < start);
+        let last = self.intervals.partition_point(|&(a, _)| a <= end);
+        if first == last {
+            return;
+        }
+
+        // What is left of the first and last intervals hit
+        let mut rest = vec![];
+        let (a, _) = self.intervals[first];
+        let (_, b) = self.intervals[last - 1];
+        if a < start {
+            rest.push((a, start - one()));
+        }
+        if b > end {
+            rest.push((end + one(), b));
+        }
+        self.intervals.splice(first..last, rest);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|&(_, b)| b < value);
+        i < self.intervals.len() && self.intervals[i].0 <= value
+    }
+
+    /// Whether every value in `self` is also in `other`.
+    pub fn is_subset(&self, other: &IntervalSet<T>) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for &(start, end) in &other.intervals {
+            result.insert(start, end);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = self.intervals[i];
+            let (c, d) = other.intervals[j];
+            if a.max(c) <= b.min(d) {
+                result.push((a.max(c), b.min(d)));
+            }
+            // Move past whichever interval ends first
+            if b < d { i += 1 } else { j += 1 }
+        }
+        IntervalSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for &(start, end) in &other.intervals {
+            result.remove(start, end);
+        }
+        result
+    }
+
+    /// Number of values in the set.
+    pub fn total_length(&self) -> T {
+        self.intervals.iter().fold(T::from(0), |total, &(start, end)| total + (end - start) + one())
+    }
+
+    /// The values missing between the first value of the set and the last, as inclusive intervals.
+    pub fn gaps(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.intervals.windows(2).map(|pair| (pair[0].1 + one(), pair[1].0 - one()))
+    }
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Integer> From<(T, T)> for IntervalSet<T> {
+    fn from((start, end): (T, T)) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(start, end);
+        set
+    }
+}
+
+impl<T: Integer> FromIterator<(T, T)> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for (start, end) in iter {
+            set.insert(start, end);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals.iter().copied().collect()
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        // (intervals to insert, resulting set)
+        let cases = [
+            (vec![(1, 1), (1, 1)], vec![(1, 1)]),
+            (vec![(5, 7), (2, 3)], vec![(2, 3), (5, 7)]),
+            (vec![(5, 7), (2, 5)], vec![(2, 7)]),
+            (vec![(2, 3), (5, 7), (4, 4)], vec![(2, 7)]),
+            (vec![(2, 3), (6, 7), (9, 10)], vec![(2, 3), (6, 7), (9, 10)]),
+            (vec![(2, 3), (6, 7), (1, 12)], vec![(1, 12)]),
+            (vec![(3, 2)], vec![])
+        ];
+
+        for (inserted, expected) in cases {
+            assert_eq!(set(&inserted).intervals(), expected.as_slice(), "inserting {:?}", inserted);
+        }
+    }
+
+    #[test]
+    fn remove_splits_intervals() {
+        let mut intervals = set(&[(0, 10), (20, 30)]);
+        intervals.remove(5, 22);
+        assert_eq!(intervals.intervals(), &[(0, 4), (23, 30)]);
+        intervals.remove(0, 0);
+        intervals.remove(40, 50);
+        assert_eq!(intervals.intervals(), &[(1, 4), (23, 30)]);
+        assert_eq!(intervals.total_length(), 12);
+        assert_eq!(intervals.gaps().collect::<Vec<_>>(), vec![(5, 22)]);
+    }
+
+    #[test]
+    fn intervals_at_the_limits() {
+        let mut intervals = set(&[(i64::MAX - 1, i64::MAX)]);
+        intervals.insert(0, 5);
+        intervals.insert(i64::MAX, i64::MAX);
+        intervals.insert(i64::MIN, i64::MIN + 1);
+        assert_eq!(intervals.intervals(), &[(i64::MIN, i64::MIN + 1), (0, 5), (i64::MAX - 1, i64::MAX)]);
+        intervals.remove(i64::MAX, i64::MAX);
+        intervals.remove(i64::MIN, i64::MIN);
+        assert_eq!(intervals.intervals(), &[(i64::MIN + 1, i64::MIN + 1), (0, 5), (i64::MAX - 1, i64::MAX - 1)]);
+
+        let mut bytes: IntervalSet<u8> = [(250, 255), (0, 0)].into_iter().collect();
+        bytes.insert(1, 249);
+        assert_eq!(bytes.intervals(), &[(0, 255)]);
+        bytes.remove(0, 255);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 5), (10, 15)]);
+        let b = set(&[(3, 12)]);
+        assert_eq!(a.union(&b).intervals(), &[(0, 15)]);
+        assert_eq!(a.intersection(&b).intervals(), &[(3, 5), (10, 12)]);
+        assert_eq!(a.difference(&b).intervals(), &[(0, 2), (13, 15)]);
+        assert!(set(&[(11, 12)]).is_subset(&a));
+        assert!(!b.is_subset(&a));
+        assert!(a.contains(15) && !a.contains(7));
+    }
+
+    // A small xorshift generator, so the random cases are the same on every run
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, n: i64) -> i64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as i64
+        }
+
+        fn intervals(&mut self) -> Vec<(i64, i64)> {
+            (0..self.below(6)).map(|_| {
+                let start = self.below(40) - 20;
+                (start, start + self.below(8))
+            }).collect()
+        }
+    }
+
+    fn model(intervals: &[(i64, i64)]) -> BTreeSet<i64> {
+        intervals.iter().flat_map(|&(start, end)| start..=end).collect()
+    }
+
+    fn values(set: &IntervalSet<i64>) -> BTreeSet<i64> {
+        model(set.intervals())
+    }
+
+    // Every operation agrees with the same operation on a plain set of values, and the intervals stay
+    // sorted with a gap between each
+    #[test]
+    fn matches_set_of_values() {
+        let mut random = Random(0x2022_1215);
+        for _ in 0..500 {
+            let (first, second) = (random.intervals(), random.intervals());
+            let (a, b) = (set(&first), set(&second));
+            let (ma, mb) = (model(&first), model(&second));
+
+            for result in [&a, &b, &a.union(&b), &a.intersection(&b), &a.difference(&b)] {
+                assert!(result.intervals().iter().all(|(start, end)| start <= end));
+                assert!(result.intervals().windows(2).all(|pair| pair[0].1 + 1 < pair[1].0));
+            }
+            assert_eq!(values(&a), ma);
+            assert_eq!(values(&a.union(&b)), &ma | &mb);
+            assert_eq!(values(&a.intersection(&b)), &ma & &mb);
+            assert_eq!(values(&a.difference(&b)), &ma - &mb);
+            assert_eq!(a.total_length(), ma.len() as i64);
+            assert_eq!(a.is_subset(&b), ma.is_subset(&mb));
+            for value in -25..35 {
+                assert_eq!(a.contains(value), ma.contains(&value));
+            }
+
+            let gaps: BTreeSet<i64> = a.gaps().flat_map(|(start, end)| start..=end).collect();
+            let expected = match (ma.first(), ma.last()) {
+                (Some(&first), Some(&last)) => (first..=last).filter(|value| !ma.contains(value)).collect(),
+                _ => BTreeSet::new()
+            };
+            assert_eq!(gaps, expected);
+        }
+    }
+}
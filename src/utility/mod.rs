@@ -1 +1,6 @@
+pub mod grid;
+pub mod intervals;
+pub mod parse_error;
+pub mod pathfinding;
+pub mod point;
 pub mod utils;
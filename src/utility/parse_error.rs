@@ -0,0 +1,149 @@
+use std::fmt::{Display, Formatter};
+
+/// Malformed input, located by 1-based line and column (in characters).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String
+}
+
+impl ParseError {
+    /// An error on the first line of whatever is being parsed; the readers in `utils` shift it to the real line.
+    pub fn new(column: usize, expected: impl Into<String>, found: impl Into<String>) -> Self {
+        ParseError {
+            file: None,
+            line: 1,
+            column,
+            expected: expected.into(),
+            found: found.into()
+        }
+    }
+
+    /// Points at `part`, which must be a slice of `line`.
+    pub fn at(line: &str, part: &str, expected: impl Into<String>) -> Self {
+        let found = if part.is_empty() { "end of line".to_string() } else { part.to_string() };
+        ParseError::new(column_of(line, part), expected, found)
+    }
+
+    /// Points just past the last line, for things that never showed up at all.
+    pub fn end_of_input(contents: &str, expected: impl Into<String>) -> Self {
+        let lines: Vec<&str> = contents.lines().collect();
+        let last = lines.iter().rposition(|line| !line.is_empty()).unwrap_or(0);
+        let column = lines.get(last).map_or(0, |line| line.chars().count()) + 1;
+        ParseError::new(column, expected, "end of input").offset(last)
+    }
+
+    pub fn offset(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+
+    /// The error message followed by the offending line of `contents` with a caret underneath.
+    pub fn render(&self, contents: &str) -> String {
+        let mut result = format!("error: expected {}, found {}\n", self.expected, self.found);
+        let location = match &self.file {
+            None => format!("line {}:{}", self.line, self.column),
+            Some(file) => format!("{}:{}:{}", file, self.line, self.column)
+        };
+
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        result.push_str(&format!("{}--> {}\n", gutter, location));
+
+        if let Some(source) = contents.lines().nth(self.line - 1) {
+            let rest: String = source.chars().skip(self.column - 1).collect();
+            let width = if !self.found.is_empty() && rest.starts_with(&self.found) {
+                self.found.chars().count()
+            } else {
+                1
+            };
+            result.push_str(&format!("{} |\n", gutter));
+            result.push_str(&format!("{} | {}\n", number, source));
+            result.push_str(&format!("{} | {}{}\n", gutter, " ".repeat(self.column - 1), "^".repeat(width)));
+        }
+
+        result.trim_end().to_string()
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}: expected {}, found {}", self.line, self.column, self.expected, self.found)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// 1-based character column of `part` inside `line`. `part` must be a slice of `line`.
+pub fn column_of(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    if offset > line.len() || !line.is_char_boundary(offset) {
+        return 1;
+    }
+    line[..offset].chars().count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_count_characters() {
+        let line = "héllo wörld";
+        assert_eq!(column_of(line, &line[0..0]), 1);
+        assert_eq!(column_of(line, &line[7..]), 7);
+        assert_eq!(column_of(line, &line[line.len()..]), 12);
+    }
+
+    #[test]
+    fn end_of_input_points_past_the_last_line() {
+        let error = ParseError::end_of_input("a\nbcd\n\n", "more");
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 4, "end of input"));
+        let error = ParseError::end_of_input("", "more");
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn render_underlines_what_was_found() {
+        let contents = "move 1 from 2 to 3\nmove 1 from x to 3\n";
+        let line = contents.lines().nth(1).unwrap();
+        let error = ParseError::at(line, &line[12..13], "a number").offset(1).with_file("day05.txt");
+        assert_eq!(error.render(contents), "\
+error: expected a number, found x
+ --> day05.txt:2:13
+  |
+2 | move 1 from x to 3
+  |             ^");
+        assert_eq!(error.to_string(), "day05.txt:2:13: expected a number, found x");
+    }
+
+    #[test]
+    fn render_gutter_and_caret_widths() {
+        let contents = format!("{}héllo wörld\n", "\n".repeat(11));
+        let line = "héllo wörld";
+        let error = ParseError::at(line, &line[7..], "hello").offset(11);
+        assert_eq!(error.render(&contents), "\
+error: expected hello, found wörld
+  --> line 12:7
+   |
+12 | héllo wörld
+   |       ^^^^^");
+
+        // Nothing to underline past the end of the line
+        let error = ParseError::end_of_input(&contents, "!");
+        assert!(error.render(&contents).ends_with("\n   |            ^"));
+        // An error on a line that isn't there only gets the location
+        assert_eq!(ParseError::new(1, "x", "y").offset(20).render(&contents), "error: expected x, found y\n  --> line 21:1");
+    }
+}
@@ -0,0 +1,260 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A route found by one of the searches, from a start node to the goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub nodes: Vec<N>,
+    pub cost: u64
+}
+
+impl<N> Path<N> {
+    /// Number of moves, one less than the number of nodes.
+    pub fn steps(&self) -> usize {
+        self.nodes.len() - 1
+    }
+}
+
+// Every search takes any number of start nodes and finds the best route from whichever of them suits.
+
+/// Breadth-first search, for graphs where every move costs 1. `neighbours` gives the nodes reachable
+/// in one move; the search stops at the first node `is_goal` accepts.
+pub fn bfs<N, S, FN, IN, FG>(starts: S, mut neighbours: FN, mut is_goal: FG) -> Option<Path<N>>
+where N: Eq + Hash + Clone,
+      S: IntoIterator<Item = N>,
+      FN: FnMut(&N) -> IN,
+      IN: IntoIterator<Item = N>,
+      FG: FnMut(&N) -> bool {
+    let mut pred = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        pred.insert(start.clone(), None);
+        queue.push_back(start);
+    }
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&current) {
+            let nodes = reconstruct(&pred, current);
+            let cost = nodes.len() as u64 - 1;
+            return Some(Path { nodes, cost });
+        }
+
+        for nb in neighbours(&current) {
+            if !pred.contains_key(&nb) {
+                pred.insert(nb.clone(), Some(current.clone()));
+                queue.push_back(nb);
+            }
+        }
+    }
+
+    None
+}
+
+/// Like `bfs` without a goal: the fewest moves from a start to every node that can be reached.
+pub fn bfs_distances<N, S, FN, IN>(starts: S, mut neighbours: FN) -> HashMap<N, usize>
+where N: Eq + Hash + Clone,
+      S: IntoIterator<Item = N>,
+      FN: FnMut(&N) -> IN,
+      IN: IntoIterator<Item = N> {
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        distances.insert(start.clone(), 0);
+        queue.push_back(start);
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let distance = distances[&current] + 1;
+        for nb in neighbours(&current) {
+            if !distances.contains_key(&nb) {
+                distances.insert(nb.clone(), distance);
+                queue.push_back(nb);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Dijkstra's algorithm; `neighbours` gives each node reachable in one move with the cost of that move.
+pub fn dijkstra<N, S, FN, IN, FG>(starts: S, neighbours: FN, is_goal: FG) -> Option<Path<N>>
+where N: Eq + Hash + Clone + Ord,
+      S: IntoIterator<Item = N>,
+      FN: FnMut(&N) -> IN,
+      IN: IntoIterator<Item = (N, u64)>,
+      FG: FnMut(&N) -> bool {
+    astar(starts, neighbours, |_| 0, is_goal)
+}
+
+/// Like `dijkstra` without a goal: the cost of the cheapest route from a start to every node that can
+/// be reached.
+pub fn dijkstra_costs<N, S, FN, IN>(starts: S, mut neighbours: FN) -> HashMap<N, u64>
+where N: Eq + Hash + Clone + Ord,
+      S: IntoIterator<Item = N>,
+      FN: FnMut(&N) -> IN,
+      IN: IntoIterator<Item = (N, u64)> {
+    let mut cost = HashMap::new();
+    let mut open = BinaryHeap::new();
+    for start in starts {
+        cost.insert(start.clone(), 0);
+        open.push(Reverse((0, start)));
+    }
+
+    while let Some(Reverse((current_cost, current))) = open.pop() {
+        if current_cost > cost[&current] {
+            continue;
+        }
+        for (nb, move_cost) in neighbours(&current) {
+            let score = current_cost + move_cost;
+            if cost.get(&nb).is_none_or(|known| score < *known) {
+                cost.insert(nb.clone(), score);
+                open.push(Reverse((score, nb)));
+            }
+        }
+    }
+
+    cost
+}
+
+/// Bellman-Ford: relaxes every move out of every one of `nodes` until no cost improves, then picks the
+/// cheapest goal. Far slower than `dijkstra`, but a useful check on it.
+pub fn bellman_ford<N, S, FN, IN, FG>(nodes: &[N], starts: S, mut neighbours: FN, mut is_goal: FG) -> Option<Path<N>>
+where N: Eq + Hash + Clone,
+      S: IntoIterator<Item = N>,
+      FN: FnMut(&N) -> IN,
+      IN: IntoIterator<Item = (N, u64)>,
+      FG: FnMut(&N) -> bool {
+    let mut pred = HashMap::new();
+    let mut cost = HashMap::new();
+    for start in starts {
+        pred.insert(start.clone(), None);
+        cost.insert(start, 0);
+    }
+
+    // Costs never go negative, so every cheapest route is found within |nodes| - 1 rounds
+    for _ in 1..nodes.len().max(1) {
+        let mut changed = false;
+        for node in nodes {
+            let Some(&node_cost) = cost.get(node) else { continue };
+            for (nb, move_cost) in neighbours(node) {
+                let score = node_cost + move_cost;
+                if cost.get(&nb).is_none_or(|known| score < *known) {
+                    cost.insert(nb.clone(), score);
+                    pred.insert(nb, Some(node.clone()));
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let goal = nodes.iter()
+        .filter(|node| cost.contains_key(*node) && is_goal(node))
+        .min_by_key(|node| cost[*node])?;
+    Some(Path { nodes: reconstruct(&pred, goal.clone()), cost: cost[goal] })
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to a goal, or the path found
+/// may not be the cheapest one.
+pub fn astar<N, S, FN, IN, FH, FG>(starts: S, mut neighbours: FN, mut heuristic: FH, mut is_goal: FG) -> Option<Path<N>>
+where N: Eq + Hash + Clone + Ord,
+      S: IntoIterator<Item = N>,
+      FN: FnMut(&N) -> IN,
+      IN: IntoIterator<Item = (N, u64)>,
+      FH: FnMut(&N) -> u64,
+      FG: FnMut(&N) -> bool {
+    let mut pred = HashMap::new();
+    let mut cost = HashMap::new();
+    // Ordered by estimated total cost; stale entries are skipped when popped
+    let mut open = BinaryHeap::new();
+
+    for start in starts {
+        pred.insert(start.clone(), None);
+        cost.insert(start.clone(), 0);
+        open.push(Reverse((heuristic(&start), 0, start)));
+    }
+
+    while let Some(Reverse((_, current_cost, current))) = open.pop() {
+        if current_cost > cost[&current] {
+            continue;
+        }
+        if is_goal(&current) {
+            return Some(Path { nodes: reconstruct(&pred, current), cost: current_cost });
+        }
+
+        for (nb, move_cost) in neighbours(&current) {
+            let score = current_cost + move_cost;
+            if cost.get(&nb).is_none_or(|known| score < *known) {
+                cost.insert(nb.clone(), score);
+                pred.insert(nb.clone(), Some(current.clone()));
+                open.push(Reverse((score + heuristic(&nb), score, nb)));
+            }
+        }
+    }
+
+    None
+}
+
+// Follows `pred` back from `goal` to the node without a predecessor, i.e. a start.
+fn reconstruct<N>(pred: &HashMap<N, Option<N>>, goal: N) -> Vec<N>
+where N: Eq + Hash + Clone {
+    let mut nodes = vec![goal];
+    while let Some(Some(previous)) = pred.get(nodes.last().unwrap()) {
+        nodes.push(previous.clone());
+    }
+    nodes.reverse();
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 costs 1 per move, the shortcut 0 -> 3 costs 5
+    fn edges(node: &u32) -> Vec<(u32, u64)> {
+        match node {
+            0 => vec![(1, 1), (3, 5)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![]
+        }
+    }
+
+    #[test]
+    fn bfs_takes_fewest_moves() {
+        let path = bfs([0], |n| edges(n).into_iter().map(|(nb, _)| nb), |n| *n == 3).unwrap();
+        assert_eq!(path.nodes, vec![0, 3]);
+        assert_eq!(path.steps(), 1);
+    }
+
+    #[test]
+    fn bfs_distances_reach_everything() {
+        let distances = bfs_distances([0], |n| edges(n).into_iter().map(|(nb, _)| nb));
+        assert_eq!(distances, HashMap::from([(0, 0), (1, 1), (2, 2), (3, 1)]));
+    }
+
+    #[test]
+    fn cost_based_searches_take_cheapest_route() {
+        let expected = Path { nodes: vec![0, 1, 2, 3], cost: 3 };
+        assert_eq!(dijkstra([0], edges, |n| *n == 3), Some(expected.clone()));
+        assert_eq!(astar([0], edges, |n| 3 - (*n as u64).min(3), |n| *n == 3), Some(expected.clone()));
+        assert_eq!(bellman_ford(&[0, 1, 2, 3], [0], edges, |n| *n == 3), Some(expected));
+    }
+
+    #[test]
+    fn several_starts() {
+        let path = dijkstra([0, 2], edges, |n| *n == 3).unwrap();
+        assert_eq!((path.nodes, path.cost), (vec![2, 3], 1));
+        assert_eq!(dijkstra_costs([1, 3], edges), HashMap::from([(1, 0), (2, 1), (3, 0)]));
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert_eq!(dijkstra([1], edges, |n| *n == 0), None);
+        assert_eq!(bellman_ford(&[0, 1, 2, 3], [1], edges, |n| *n == 0), None);
+        assert_eq!(bfs([3], |n| edges(n).into_iter().map(|(nb, _)| nb), |n| *n == 0), None);
+    }
+}
@@ -1,18 +1,152 @@
 use std::fs::read_to_string;
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use crate::utility::parse_error::ParseError;
 
-pub fn parse_file<F, R>(path: &str, line_fn: F) -> Vec<R>
-where F: Fn(&str) -> R {
-    let contents = match read_to_string(path) {
-        Ok(contents) => contents,
-        Err(error) => panic!("Can't open file: {:?}", error)
-    };
-    let lines = contents.split("\r\n");
-
-    let mut result: Vec<R> = Vec::new();
-    for line in lines {
-        if line.is_empty() {continue};
-        result.push(line_fn(line));
+pub fn read_file(path: &str) -> Result<String, String> {
+    read_to_string(path).map_err(|error| format!("Can't open file {}: {}", path, error))
+}
+
+/// Runs `f`, turning a panic into an error with the panic's message, so one broken day can be
+/// reported without taking everything else down with it.
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown panic".to_string()
+        };
+        format!("panicked: {}", message)
+    })
+}
+
+/// Runs `f` with the panic hook turned off, for code that catches its panics and reports them itself.
+/// The hook is put back afterwards, even if `f` panics.
+pub fn without_panic_messages<R>(f: impl FnOnce() -> R) -> R {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+    result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+/// Non-blank lines of the input. Works for both LF and CRLF files.
+pub fn lines(contents: &str) -> impl Iterator<Item = &str> {
+    numbered_lines(contents).map(|(_, line)| line)
+}
+
+/// Like `lines`, but each line comes with its 0-based index in the file, for error reporting.
+pub fn numbered_lines(contents: &str) -> impl Iterator<Item = (usize, &str)> {
+    contents.lines().enumerate().filter(|(_, line)| !line.is_empty())
+}
+
+/// Groups of consecutive non-blank lines; any run of blank lines ends a group.
+pub fn blocks(contents: &str) -> Vec<Vec<&str>> {
+    numbered_blocks(contents).into_iter().map(|(_, block)| block).collect()
+}
+
+fn numbered_blocks(contents: &str) -> Vec<(usize, Vec<&str>)> {
+    let mut result = vec![];
+    let mut block = vec![];
+    let mut start = 0;
+    for (i, line) in contents.lines().enumerate() {
+        if line.is_empty() {
+            if !block.is_empty() {
+                result.push((start, block));
+                block = vec![];
+            }
+        } else {
+            if block.is_empty() {
+                start = i;
+            }
+            block.push(line);
+        }
+    }
+
+    if !block.is_empty() {
+        result.push((start, block));
     }
 
     result
 }
+
+/// The whole input as one string, without the trailing line break.
+pub fn whole(contents: &str) -> &str {
+    contents.trim_end_matches(['\r', '\n'])
+}
+
+/// Parses every non-blank line; errors from `line_fn` are moved to the line they came from.
+pub fn parse_lines<F, R>(contents: &str, line_fn: F) -> Result<Vec<R>, ParseError>
+where F: Fn(&str) -> Result<R, ParseError> {
+    numbered_lines(contents)
+        .map(|(i, line)| line_fn(line).map_err(|error| error.offset(i)))
+        .collect()
+}
+
+/// Parses every block; errors from `block_fn` are relative to the block's first line.
+pub fn parse_blocks<F, R>(contents: &str, block_fn: F) -> Result<Vec<R>, ParseError>
+where F: Fn(&[&str]) -> Result<R, ParseError> {
+    numbered_blocks(contents)
+        .iter()
+        .map(|(start, block)| block_fn(block).map_err(|error| error.offset(*start)))
+        .collect()
+}
+
+/// Parses `part` (a slice of `line`) as a number, pointing at it if that fails.
+pub fn parse_number<T: FromStr>(line: &str, part: &str) -> Result<T, ParseError> {
+    part.parse().map_err(|_| ParseError::at(line, part, "a number"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panics_become_errors() {
+        assert_eq!(catch_panic(|| 1), Ok(1));
+        assert_eq!(catch_panic(|| -> i32 { panic!("day {}", 7) }), Err("panicked: day 7".to_string()));
+    }
+
+    #[test]
+    fn lf_and_crlf_read_the_same() {
+        let lf = "1\n2\n\n3\n";
+        let crlf = "1\r\n2\r\n\r\n3\r\n";
+        for contents in [lf, crlf] {
+            assert_eq!(lines(contents).collect::<Vec<_>>(), vec!["1", "2", "3"]);
+            assert_eq!(numbered_lines(contents).collect::<Vec<_>>(), vec![(0, "1"), (1, "2"), (3, "3")]);
+            assert_eq!(blocks(contents), vec![vec!["1", "2"], vec!["3"]]);
+            assert_eq!(whole(contents), contents.trim_end());
+        }
+    }
+
+    #[test]
+    fn blocks_split_on_any_run_of_blank_lines() {
+        assert_eq!(blocks("\n\na\nb\n\n\n\nc\n\n"), vec![vec!["a", "b"], vec!["c"]]);
+        assert_eq!(blocks("a\r\n\r\n\r\nb"), vec![vec!["a"], vec!["b"]]);
+        assert!(blocks("\n\n").is_empty());
+    }
+
+    #[test]
+    fn missing_trailing_newline() {
+        assert_eq!(lines("1\n2").collect::<Vec<_>>(), vec!["1", "2"]);
+        assert_eq!(blocks("a\n\nb"), vec![vec!["a"], vec!["b"]]);
+        assert_eq!(whole("abc"), "abc");
+    }
+
+    #[test]
+    fn errors_point_at_the_line_in_the_file() {
+        let number = |line: &str| parse_number::<i32>(line, line);
+        let error = parse_lines("1\n\n2\r\nx\n", number).unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (4, 1, "x"));
+
+        // Line 3 of the second block, which starts on line 5
+        let block = |block: &[&str]| {
+            block.iter().enumerate().map(|(i, line)| number(line).map_err(|error| error.offset(i))).collect::<Result<Vec<_>, _>>()
+        };
+        let error = parse_blocks("1\n2\n\n\n3\n4\n 5\n", block).unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (7, 1, " 5"));
+        assert_eq!(parse_blocks("1\r\n\r\n2\r\n3", block), Ok(vec![vec![1], vec![2, 3]]));
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt::{Display, Formatter};
+use crate::answer::Answer;
+use crate::days::{self, Day, Part};
+use crate::settings::Settings;
+use crate::utility::parse_error::ParseError;
+use crate::utility::utils::{catch_panic, numbered_lines, parse_number, read_file, without_panic_messages};
+
+/// One known-good answer: `<day> <part> <input path> <answer>`, one per line in the answers file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownAnswer {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+    pub expected: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing, // No answer recorded for this day, part and input
+    NoInput, // The input file isn't there, so nothing was run
+    Error(String)
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Missing => write!(f, "missing"),
+            Status::NoInput => write!(f, "no input"),
+            Status::Error(error) => write!(f, "ERROR {}", error)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub status: Status
+}
+
+impl Check {
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Fail | Status::Error(_))
+    }
+}
+
+/// Blank lines and lines starting with `#` are ignored. A literal `\n` in an answer stands for a line break.
+pub fn parse_answers(contents: &str) -> Result<Vec<KnownAnswer>, ParseError> {
+    numbered_lines(contents)
+        .filter(|(_, line)| !line.starts_with('#'))
+        .map(|(i, line)| parse_answer(line).map_err(|error| error.offset(i)))
+        .collect()
+}
+
+fn parse_answer(line: &str) -> Result<KnownAnswer, ParseError> {
+    let mut fields = line.splitn(4, ' ');
+    let mut field = |expected: &str| match fields.next() {
+        Some(field) if !field.is_empty() => Ok(field),
+        _ => Err(ParseError::at(line, &line[line.len()..], expected))
+    };
+
+    let day = field("a day")?;
+    let part = field("a part")?;
+    let input = field("an input path")?;
+    let expected = field("an answer")?;
+
+    Ok(KnownAnswer {
+        day: parse_number(line, day)?,
+        part: part.parse().map_err(|_| ParseError::at(line, part, "1 or 2"))?,
+        input: input.to_string(),
+        expected: expected.replace("\\n", "\n")
+    })
+}
+
+/// Runs every registered day on each input it has answers for (or its default input) and compares.
+pub fn verify(answers: &[KnownAnswer]) -> Vec<Check> {
+    let mut checks = vec![];
+    for day in days::DAYS.iter() {
+        let mut inputs: Vec<String> = vec![];
+        for answer in answers.iter().filter(|answer| answer.day == day.number) {
+            if !inputs.contains(&answer.input) {
+                inputs.push(answer.input.clone());
+            }
+        }
+        if inputs.is_empty() {
+            inputs.push(day.input_path());
+        }
+
+        for input in inputs {
+            checks.extend(verify_input(day, &input, answers));
+        }
+    }
+
+    checks
+}
+
+fn verify_input(day: &Day, input: &str, answers: &[KnownAnswer]) -> Vec<Check> {
+    let results = read_file(input).ok().map(|contents| {
+        // A day that panics is as broken as one that gets the wrong answer
+        without_panic_messages(|| catch_panic(|| day.run(&contents, &Part::BOTH, &Settings::default())))
+            .and_then(|results| results.map_err(|error| error.with_file(input).to_string()))
+    });
+    checks(day.number, input, answers, results)
+}
+
+// One check per part, given what running the day on `input` gave, or `None` if there was no input
+fn checks(day: u8, input: &str, answers: &[KnownAnswer], results: Option<Result<Vec<(Part, Answer)>, String>>) -> Vec<Check> {
+    let expected = |part: Part| answers.iter()
+        .find(|answer| answer.day == day && answer.part == part && answer.input == input)
+        .map(|answer| answer.expected.clone());
+    let check = |part: Part, actual: Option<String>, status: Status| Check {
+        day,
+        part,
+        input: input.to_string(),
+        expected: expected(part),
+        actual,
+        status
+    };
+
+    match results {
+        None => Part::BOTH.iter().map(|part| check(*part, None, Status::NoInput)).collect(),
+        Some(Err(error)) => Part::BOTH.iter()
+            .map(|part| check(*part, None, Status::Error(error.clone())))
+            .collect(),
+        Some(Ok(results)) => results.into_iter().map(|(part, actual)| {
+            let actual = actual.to_string();
+            let status = match expected(part) {
+                None => Status::Missing,
+                Some(expected) if expected == actual => Status::Pass,
+                Some(_) => Status::Fail
+            };
+            check(part, Some(actual), status)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_file() {
+        let answers = parse_answers("# day 10\n1 2 inputs/day01.txt 45000\n\n10 2 example.txt #.\\n.#\n").unwrap();
+        assert_eq!(answers, vec![
+            KnownAnswer { day: 1, part: Part::Two, input: "inputs/day01.txt".to_string(), expected: "45000".to_string() },
+            KnownAnswer { day: 10, part: Part::Two, input: "example.txt".to_string(), expected: "#.\n.#".to_string() }
+        ]);
+        // Answers can have spaces in them
+        assert_eq!(parse_answers("5 1 in.txt C M Z").unwrap()[0].expected, "C M Z");
+
+        // (line, column of the error, expected)
+        let cases = [
+            ("1 3 in.txt 5", 3, "1 or 2"),
+            ("x 1 in.txt 5", 1, "a number"),
+            ("1 1 in.txt", 11, "an answer"),
+            ("1 1  5", 7, "an input path"),
+            ("1", 2, "a part")
+        ];
+        for (line, column, expected) in cases {
+            let error = parse_answers(&format!("# header\n{}", line)).unwrap_err();
+            assert_eq!((error.line, error.column, error.expected.as_str()), (2, column, expected), "{}", line);
+        }
+    }
+
+    #[test]
+    fn statuses() {
+        let answers = parse_answers("10 1 in.txt 13140\n10 2 in.txt #.\\n.#\n").unwrap();
+        let statuses = |results| -> Vec<Status> {
+            checks(10, "in.txt", &answers, results).into_iter().map(|check| check.status).collect()
+        };
+        let grid = Answer::Grid(vec!["#.".to_string(), ".#".to_string()]);
+
+        assert_eq!(statuses(Some(Ok(vec![(Part::One, Answer::Integer(13140)), (Part::Two, grid.clone())]))),
+                   vec![Status::Pass, Status::Pass]);
+        assert_eq!(statuses(Some(Ok(vec![(Part::One, Answer::Integer(1)), (Part::Two, grid.clone())]))),
+                   vec![Status::Fail, Status::Pass]);
+        assert_eq!(statuses(None), vec![Status::NoInput, Status::NoInput]);
+        assert_eq!(statuses(Some(Err("panicked: oops".to_string()))),
+                   vec![Status::Error("panicked: oops".to_string()), Status::Error("panicked: oops".to_string())]);
+
+        let other = checks(10, "other.txt", &answers, Some(Ok(vec![(Part::Two, grid)])));
+        assert_eq!((other[0].status.clone(), other[0].actual.as_deref()), (Status::Missing, Some("#.\n.#")));
+        assert!(!other[0].failed());
+    }
+}